@@ -0,0 +1,92 @@
+// Champ-select overlay fed by the local API's event stream.
+// Open as /overlay?token=<api token>.
+(function () {
+  const token = new URLSearchParams(location.search).get("token") || "";
+  const phases = {
+    PLANNING: "Planning",
+    BAN_PICK: "Ban/Pick",
+    FINALIZATION: "Finalization",
+    GAME_STARTING: "Game starting",
+  };
+
+  let deadline = null;
+
+  function championName(champion) {
+    return champion.name || "Champion " + champion.id;
+  }
+
+  function render(champSelect) {
+    document.body.classList.toggle("idle", !champSelect);
+    if (!champSelect) {
+      deadline = null;
+      return;
+    }
+
+    document.getElementById("phase").textContent =
+      phases[champSelect.phase] || champSelect.phase;
+    deadline =
+      champSelect.time_left_ms === null ? null : Date.now() + champSelect.time_left_ms;
+    updateTimer();
+
+    const team = document.getElementById("team");
+    team.replaceChildren(
+      ...champSelect.my_team.map(function (cell) {
+        const item = document.createElement("li");
+        item.className = "cell";
+        item.classList.toggle("locked", cell.locked);
+        item.classList.toggle("hovering", !!cell.champion && !cell.locked);
+        item.classList.toggle("acting", cell.acting);
+        item.classList.toggle("local", cell.is_local_player);
+
+        const position = document.createElement("span");
+        position.className = "position";
+        position.textContent = cell.position || "";
+        const champion = document.createElement("span");
+        champion.className = "champion";
+        champion.textContent = cell.champion ? championName(cell.champion) : "-";
+
+        item.append(position, champion);
+        return item;
+      })
+    );
+
+    const bans = document.getElementById("bans");
+    bans.replaceChildren(
+      ...champSelect.my_team_bans.map(function (champion) {
+        const ban = document.createElement("span");
+        ban.className = "ban";
+        ban.textContent = championName(champion);
+        return ban;
+      })
+    );
+  }
+
+  function updateTimer() {
+    const timer = document.getElementById("timer");
+    timer.textContent =
+      deadline === null ? "" : Math.max(0, Math.ceil((deadline - Date.now()) / 1000)) + "s";
+  }
+
+  function connect() {
+    const scheme = location.protocol === "https:" ? "wss:" : "ws:";
+    const socket = new WebSocket(
+      scheme + "//" + location.host + "/api/events?token=" + encodeURIComponent(token)
+    );
+    socket.onmessage = function (message) {
+      const event = JSON.parse(message.data);
+      if (event.type === "snapshot") {
+        render(event.state.champ_select);
+      } else if (event.type === "champ_select") {
+        render(event.champ_select);
+      }
+    };
+    // The app may restart or the token may be wrong; keep retrying quietly
+    socket.onclose = function () {
+      render(null);
+      setTimeout(connect, 2000);
+    };
+  }
+
+  setInterval(updateTimer, 250);
+  connect();
+})();
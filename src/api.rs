@@ -0,0 +1,332 @@
+use crate::config::{self, ApiConfig, Config};
+use tracing::{info, warn};
+use crate::overlay;
+use crate::state::{SharedState, StateEvent};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rand::distributions::{Alphanumeric, DistString};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+const TOKEN_FILE: &str = "api-token";
+const TOKEN_LEN: usize = 32;
+
+struct ApiState {
+    app: SharedState,
+    token: String,
+}
+
+/// Serves the local API and the overlay on 127.0.0.1 in the background
+pub fn start(config: &Config, app: SharedState) {
+    let api_config = &config.api;
+    let token = match resolve_token(api_config) {
+        Ok(token) => token,
+        Err(e) => {
+            warn!("Local API disabled: {}", e);
+            return;
+        }
+    };
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, api_config.port));
+    let router = router(Arc::new(ApiState { app, token }))
+        .merge(overlay::router(config.overlay.theme_path()));
+
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Failed to start local API on {}: {}", addr, e);
+                return;
+            }
+        };
+        info!("Local API listening on http://{}", addr);
+        info!("Overlay: http://{}/overlay?token=<api token>", addr);
+        if let Err(e) = axum::serve(listener, router).await {
+            warn!("Local API stopped: {}", e);
+        }
+    });
+}
+
+fn router(api: Arc<ApiState>) -> Router {
+    Router::new()
+        .route("/api/state", get(get_state))
+        .route("/api/connection", get(get_connection))
+        .route("/api/phase", get(get_phase))
+        .route("/api/champ-select", get(get_champ_select))
+        .route("/api/links", get(get_links))
+        .route("/api/automations/pause", post(pause_automations))
+        .route("/api/automations/resume", post(resume_automations))
+        .route("/api/multi-search", post(trigger_multi_search))
+        .route("/api/events", get(events))
+        .route_layer(middleware::from_fn_with_state(api.clone(), require_token))
+        .with_state(api)
+}
+
+/// Accepts `Authorization: Bearer <token>`, or `?token=<token>` for browsers
+/// that can't set headers on a WebSocket
+async fn require_token(State(api): State<Arc<ApiState>>, request: Request, next: Next) -> Response {
+    let from_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.to_string());
+    let from_query = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .and_then(|token| urlencoding::decode(token).ok())
+            .map(|token| token.into_owned())
+    });
+
+    if from_header.or(from_query).as_deref() != Some(api.token.as_str()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+async fn get_state(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    Json(api.app.lock().unwrap().snapshot())
+}
+
+async fn get_connection(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    let app = api.app.lock().unwrap();
+    Json(serde_json::json!({
+        "connected": app.lcu.connected,
+        "port": app.lcu.data.as_ref().map(|info| info.port),
+    }))
+}
+
+async fn get_phase(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    Json(serde_json::json!({ "phase": api.app.lock().unwrap().current_phase() }))
+}
+
+async fn get_champ_select(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    Json(api.app.lock().unwrap().champ_select.clone())
+}
+
+async fn get_links(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    Json(api.app.lock().unwrap().last_links.clone())
+}
+
+async fn pause_automations(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    set_paused(&api, true)
+}
+
+async fn resume_automations(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    set_paused(&api, false)
+}
+
+fn set_paused(api: &ApiState, paused: bool) -> impl IntoResponse {
+    let mut app = api.app.lock().unwrap();
+    if app.automations_paused != paused {
+        app.set_automations_paused(paused);
+        let action = if paused {
+            "Automations paused"
+        } else {
+            "Automations resumed"
+        };
+        app.record_action(action.to_string());
+    }
+    Json(app.snapshot())
+}
+
+/// Picked up by the polling loop on its next tick
+async fn trigger_multi_search(State(api): State<Arc<ApiState>>) -> impl IntoResponse {
+    api.app.lock().unwrap().multi_search_requested = true;
+    StatusCode::ACCEPTED
+}
+
+async fn events(ws: WebSocketUpgrade, State(api): State<Arc<ApiState>>) -> Response {
+    let app = api.app.clone();
+    ws.on_upgrade(move |socket| stream_events(socket, app))
+}
+
+/// Sends a snapshot, then every state change until the client goes away
+async fn stream_events(mut socket: WebSocket, app: SharedState) {
+    let (mut events, snapshot) = {
+        let app = app.lock().unwrap();
+        (app.subscribe(), app.snapshot())
+    };
+    if send_event(&mut socket, &StateEvent::Snapshot { state: snapshot })
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    // Missed some events, so resync with a full snapshot
+                    Err(RecvError::Lagged(_)) => StateEvent::Snapshot {
+                        state: app.lock().unwrap().snapshot(),
+                    },
+                    Err(RecvError::Closed) => return,
+                };
+                if send_event(&mut socket, &event).await.is_err() {
+                    return;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+async fn send_event(socket: &mut WebSocket, event: &StateEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+/// The configured token, else one saved from a previous run, else a new one
+fn resolve_token(api_config: &ApiConfig) -> Result<String, String> {
+    if let Some(token) = &api_config.token {
+        return Ok(token.clone());
+    }
+
+    let dir = config::config_dir().ok_or("no config directory to store the API token in")?;
+    let path = dir.join(TOKEN_FILE);
+    if let Ok(token) = std::fs::read_to_string(&path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let token = Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LEN);
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, &token))
+        .map_err(|e| format!("Failed to save API token to {}: {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    info!("Generated local API token in {}", path.display());
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "s3cret token";
+
+    fn api() -> (Router, SharedState) {
+        let app = state::new_shared_state();
+        let router = router(Arc::new(ApiState {
+            app: app.clone(),
+            token: TOKEN.to_string(),
+        }));
+        (router, app)
+    }
+
+    fn request(method: &str, uri: &str, token: Option<&str>) -> Request {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn send(router: Router, request: Request) -> (StatusCode, serde_json::Value) {
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+        (status, json)
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_token() {
+        let (router, _) = api();
+        let (status, _) = send(router.clone(), request("GET", "/api/state", None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = send(router.clone(), request("GET", "/api/state", Some("wrong"))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = send(router, request("GET", "/api/state?token=wrong", None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn accepts_the_token_in_a_header_or_the_query() {
+        let (router, _) = api();
+        let (status, state) = send(router.clone(), request("GET", "/api/state", Some(TOKEN))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(state["connected"], false);
+
+        // Percent-encoded, as a browser sends it
+        let uri = "/api/state?theme=dark&token=s3cret%20token";
+        let (status, _) = send(router, request("GET", uri, None)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn state_endpoints_read_the_shared_state() {
+        let (router, app) = api();
+        {
+            let mut app = app.lock().unwrap();
+            app.record_phase("ChampSelect");
+            app.record_links(vec!["https://www.op.gg/multisearch/euw".to_string()]);
+        }
+
+        let (_, phase) = send(router.clone(), request("GET", "/api/phase", Some(TOKEN))).await;
+        assert_eq!(phase["phase"], "ChampSelect");
+
+        let (_, links) = send(router.clone(), request("GET", "/api/links", Some(TOKEN))).await;
+        assert_eq!(
+            links,
+            serde_json::json!(["https://www.op.gg/multisearch/euw"])
+        );
+
+        let (_, connection) = send(router, request("GET", "/api/connection", Some(TOKEN))).await;
+        assert_eq!(connection["connected"], false);
+        assert_eq!(connection["port"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_automations() {
+        let (router, app) = api();
+        let (status, state) = send(
+            router.clone(),
+            request("POST", "/api/automations/pause", Some(TOKEN)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(state["automations_paused"], true);
+        assert!(app.lock().unwrap().automations_paused);
+
+        let (_, state) = send(
+            router,
+            request("POST", "/api/automations/resume", Some(TOKEN)),
+        )
+        .await;
+        assert_eq!(state["automations_paused"], false);
+        assert!(!app.lock().unwrap().automations_paused);
+    }
+
+    #[tokio::test]
+    async fn multi_search_is_left_to_the_poll_loop() {
+        let (router, app) = api();
+        let (status, _) = send(router, request("POST", "/api/multi-search", Some(TOKEN))).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(app.lock().unwrap().multi_search_requested);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::config::BrowserConfig;
+use tracing::{debug, warn};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const URL_PLACEHOLDER: &str = "{url}";
+
+pub struct Browser {
+    launcher: Option<Launcher>,
+}
+
+struct Launcher {
+    executable: PathBuf,
+    args: Vec<String>,
+}
+
+impl Browser {
+    /// Builds the browser from config, checking up front that the configured
+    /// executable exists so a typo is reported at startup rather than at the
+    /// first champ select.
+    pub fn from_config(config: Option<&BrowserConfig>) -> Result<Self, String> {
+        let config = match config {
+            Some(config) => config,
+            None => return Ok(Browser { launcher: None }),
+        };
+
+        let executable = resolve_executable(&config.executable)
+            .ok_or_else(|| format!("Browser executable not found: {}", config.executable))?;
+
+        Ok(Browser {
+            launcher: Some(Launcher {
+                executable,
+                args: config.args.clone(),
+            }),
+        })
+    }
+
+    pub fn open(&self, url: &str) {
+        if let Some(launcher) = &self.launcher {
+            match launcher.spawn(url) {
+                Ok(_) => {
+                    debug!("Successfully opened link in {}", launcher.executable.display());
+                    return;
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to launch {} ({:?}), falling back to the system default browser",
+                        launcher.executable.display(),
+                        e
+                    );
+                }
+            }
+        }
+
+        match open::that(url) {
+            Ok(_) => {
+                debug!("Successfully opened link in browser");
+            }
+            Err(e) => {
+                warn!("Failed to open link in browser: {:?}", e);
+            }
+        }
+    }
+}
+
+impl Launcher {
+    fn spawn(&self, url: &str) -> std::io::Result<()> {
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.replace(URL_PLACEHOLDER, url))
+            .collect();
+        if !self.args.iter().any(|arg| arg.contains(URL_PLACEHOLDER)) {
+            args.push(url.to_string());
+        }
+
+        let mut child = Command::new(&self.executable).args(&args).spawn()?;
+        // Reap the child in the background so finished launchers don't linger as zombies
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+}
+
+pub fn resolve_executable(executable: &str) -> Option<PathBuf> {
+    let path = Path::new(executable);
+    if path.components().count() > 1 {
+        return is_executable(path).then(|| path.to_path_buf());
+    }
+
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(executable))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
@@ -1,26 +1,57 @@
 use serde::{Deserialize, Serialize};
+use crate::lcu_client::LCUClient;
+use tracing::warn;
+use urlencoding::encode;
 
+/// Fields the app doesn't read default, so a renamed or dropped one doesn't
+/// break ordering, the live view, the overlay or the dodge
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChampSelectSession {
+    pub actions: Vec<Vec<Action>>,
+    #[serde(default)]
     pub allow_battle_boost: bool,
+    #[serde(default)]
     pub allow_duplicate_picks: bool,
+    #[serde(default)]
     pub allow_locked_events: bool,
+    #[serde(default)]
     pub allow_rerolling: bool,
+    #[serde(default)]
     pub allow_skin_selection: bool,
+    #[serde(default)]
+    pub bans: Bans,
+    #[serde(default)]
     pub bench_enabled: bool,
+    #[serde(default)]
     pub boostable_skin_count: i64,
+    #[serde(default)]
+    pub chat_details: ChatDetails,
+    #[serde(default)]
     pub counter: i64,
+    #[serde(default)]
     pub game_id: u64,
+    #[serde(default)]
     pub has_simultaneous_bans: bool,
+    #[serde(default)]
     pub has_simultaneous_picks: bool,
+    #[serde(default)]
     pub is_custom_game: bool,
+    #[serde(default)]
     pub is_spectating: bool,
     pub local_player_cell_id: i64,
+    #[serde(default)]
     pub locked_event_index: i64,
+    pub my_team: Vec<Team>,
+    #[serde(default)]
+    pub pick_order_swaps: Vec<PickOrderSwap>,
+    #[serde(default)]
     pub recovery_counter: i64,
+    #[serde(default)]
     pub rerolls_remaining: i64,
+    #[serde(default)]
     pub skip_champion_select: bool,
+    pub their_team: Vec<Team>,
     pub timer: Timer,
 }
 
@@ -30,6 +61,7 @@ pub struct Action {
     pub actor_cell_id: i64,
     pub champion_id: i64,
     pub completed: bool,
+    #[serde(default)]
     pub id: i64,
     pub is_ally_action: bool,
     pub is_in_progress: bool,
@@ -46,7 +78,7 @@ pub enum Type {
     TenBansReveal,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Bans {
     pub my_team_bans: Vec<Option<serde_json::Value>>,
@@ -54,15 +86,17 @@ pub struct Bans {
     pub their_team_bans: Vec<Option<serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatDetails {
+    #[serde(default)]
     pub muc_jwt_dto: MucJwtDto,
     pub multi_user_chat_id: String,
+    #[serde(default)]
     pub multi_user_chat_password: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MucJwtDto {
     pub channel_claim: String,
@@ -78,15 +112,21 @@ pub struct Team {
     pub cell_id: i64,
     pub champion_id: i64,
     pub champion_pick_intent: i64,
-    pub name_visibility_type: NameVisibilityType,
+    #[serde(default)]
+    pub name_visibility_type: Option<NameVisibilityType>,
+    #[serde(default)]
     pub obfuscated_puuid: String,
+    #[serde(default)]
     pub obfuscated_summoner_id: i64,
     pub puuid: String,
+    #[serde(default)]
     pub selected_skin_id: i64,
     pub spell1_id: i64,
     pub spell2_id: i64,
+    #[serde(default)]
     pub summoner_id: i64,
     pub team: i64,
+    #[serde(default)]
     pub ward_skin_id: i64,
 }
 
@@ -110,8 +150,58 @@ pub struct PickOrderSwap {
 #[serde(rename_all = "camelCase")]
 pub struct Timer {
     pub adjusted_time_left_in_phase: u64,
+    #[serde(default)]
     pub internal_now_in_epoch_ms: u64,
     pub is_infinite: bool,
     pub phase: String,
+    #[serde(default)]
     pub total_time_in_phase: i64,
-}
\ No newline at end of file
+}
+
+impl ChampSelectSession {
+    pub fn local_player(&self) -> Option<&Team> {
+        self.my_team
+            .iter()
+            .find(|member| member.cell_id == self.local_player_cell_id)
+    }
+
+    /// Our pick or ban that is currently in progress
+    pub fn local_player_action(&self) -> Option<&Action> {
+        self.actions.iter().flatten().find(|action| {
+            action.actor_cell_id == self.local_player_cell_id
+                && action.is_in_progress
+                && !action.completed
+                && !matches!(action.action_type, Type::TenBansReveal)
+        })
+    }
+}
+
+pub async fn get_champ_select_session(remoting_client: &LCUClient) -> Option<ChampSelectSession> {
+    let response = remoting_client
+        .get("/lol-champ-select/v1/session")
+        .await
+        .ok()?;
+
+    match response.json().await {
+        Ok(session) => Some(session),
+        Err(e) => {
+            warn!("Failed to parse champ select session: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Leaves champ select (a dodge), with the usual dodge penalties
+pub async fn dodge(remoting_client: &LCUClient) -> Result<(), Box<dyn std::error::Error>> {
+    let args = encode(r#"["","teambuilder-draft","quitV2",""]"#);
+    remoting_client
+        .post(
+            &format!(
+                "/lol-login/v1/session/invoke?destination=lcdsServiceProxy&method=call&args={}",
+                args
+            ),
+            serde_json::json!({}),
+        )
+        .await?;
+    Ok(())
+}
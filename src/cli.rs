@@ -0,0 +1,62 @@
+use crate::codegen::CodegenArgs;
+use crate::drift::DriftArgs;
+use crate::journal::JournalArgs;
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Opens multi-search links and accepts ready checks for the League Client")]
+pub struct Cli {
+    /// Run as a background service: no terminal UI or live champ-select view
+    #[arg(long)]
+    pub daemon: bool,
+    /// Runs the console when no subcommand is given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Show recorded champ selects, ready checks, accepts, dodges and games
+    Journal(JournalArgs),
+    /// Print a systemd user unit that runs the console with --daemon
+    SystemdUnit,
+    /// Print whether the League Client is running and the gameflow phase
+    Status(OutputArgs),
+    /// Accept the current ready check
+    Accept(OutputArgs),
+    /// Decline the current ready check
+    Decline(OutputArgs),
+    /// Print the champ-select team, or the premade lobby outside champ select
+    Lobby(OutputArgs),
+    /// Print the multi-search link for the current champ select
+    Link(OutputArgs),
+    /// Print the logged-in account
+    Summoner(OutputArgs),
+    /// Call any League Client endpoint
+    Raw(RawArgs),
+    /// Interactive shell for League Client endpoints and live events
+    Repl,
+    /// Generate typed endpoint functions and models from a saved schema dump
+    Codegen(CodegenArgs),
+    /// Check the client's responses against our models and write a report
+    SchemaDrift(DriftArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// Print JSON instead of text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RawArgs {
+    /// HTTP method, e.g. GET or POST
+    pub method: String,
+    /// Endpoint path, e.g. /lol-gameflow/v1/gameflow-phase
+    pub path: String,
+    /// JSON request body
+    pub body: Option<String>,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
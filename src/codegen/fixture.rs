@@ -0,0 +1,207 @@
+// Generated by `league-reveal-console codegen` from lcu-help.json (lol-summoner, lol-lobby). Do not edit by hand.
+#![allow(dead_code, clippy::all)]
+
+use crate::lcu_client::{LCUClient, LcuError};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// `null` reads as the default, like a missing field
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobby {
+    #[serde(rename = "partyId", deserialize_with = "null_as_default")]
+    pub party_id: String,
+    #[serde(rename = "canStartActivity", deserialize_with = "null_as_default")]
+    pub can_start_activity: bool,
+    #[serde(deserialize_with = "null_as_default")]
+    pub members: Vec<LolLobbyLobbyMember>,
+    #[serde(rename = "localMember", deserialize_with = "null_as_default")]
+    pub local_member: LolLobbyLobbyMember,
+    #[serde(rename = "gameConfig", deserialize_with = "null_as_default")]
+    pub game_config: LolLobbyLobbyGameConfig,
+    #[serde(deserialize_with = "null_as_default")]
+    pub invitations: Vec<LolLobbyLobbyInvitation>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum LolLobbyLobbyBotDifficulty {
+    #[serde(rename = "NONE")]
+    None,
+    #[serde(rename = "EASY")]
+    Easy,
+    #[serde(rename = "MEDIUM")]
+    Medium,
+    #[serde(rename = "HARD")]
+    Hard,
+    #[serde(rename = "UBER")]
+    Uber,
+    #[serde(rename = "TUTORIAL")]
+    Tutorial,
+    #[serde(rename = "INTRO")]
+    Intro,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyChangeGameDto {
+    #[serde(rename = "queueId", deserialize_with = "null_as_default")]
+    pub queue_id: i32,
+    #[serde(rename = "isCustom", deserialize_with = "null_as_default")]
+    pub is_custom: bool,
+    #[serde(rename = "customGameLobby")]
+    pub custom_game_lobby: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyGameConfig {
+    #[serde(rename = "queueId", deserialize_with = "null_as_default")]
+    pub queue_id: i32,
+    #[serde(rename = "maxLobbySize", deserialize_with = "null_as_default")]
+    pub max_lobby_size: i32,
+    #[serde(rename = "customSpectatorPolicy", deserialize_with = "null_as_default")]
+    pub custom_spectator_policy: String,
+    #[serde(rename = "positionCounts", deserialize_with = "null_as_default")]
+    pub position_counts: HashMap<String, i32>,
+    #[serde(rename = "customTeam100", deserialize_with = "null_as_default")]
+    pub custom_team100: Vec<LolLobbyLobbyMember>,
+    #[serde(rename = "customMutatorName")]
+    pub custom_mutator_name: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyInvitation {
+    #[serde(rename = "invitationId", deserialize_with = "null_as_default")]
+    pub invitation_id: String,
+    #[serde(rename = "toSummonerName", deserialize_with = "null_as_default")]
+    pub to_summoner_name: String,
+    pub state: serde_json::Value,
+    #[serde(rename = "replacedBy")]
+    pub replaced_by: Option<Box<LolLobbyLobbyInvitation>>,
+    pub origin: Option<Box<LolLobbyLobbyInvitationOrigin>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyInvitationDto {
+    #[serde(rename = "toSummonerId", deserialize_with = "null_as_default")]
+    pub to_summoner_id: u64,
+    pub state: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyInvitationOrigin {
+    #[serde(deserialize_with = "null_as_default")]
+    pub r#type: String,
+    pub invitation: Option<Box<LolLobbyLobbyInvitation>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolLobbyLobbyMember {
+    #[serde(deserialize_with = "null_as_default")]
+    pub puuid: String,
+    #[serde(rename = "isLeader", deserialize_with = "null_as_default")]
+    pub is_leader: bool,
+    #[serde(rename = "firstPositionPreference", deserialize_with = "null_as_default")]
+    pub first_position_preference: String,
+    #[serde(rename = "botDifficulty", deserialize_with = "null_as_default")]
+    pub bot_difficulty: LolLobbyLobbyBotDifficulty,
+    #[serde(rename = "self", deserialize_with = "null_as_default")]
+    pub self_: bool,
+    #[serde(rename = "super", deserialize_with = "null_as_default")]
+    pub super_: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum LolSummonerProfilePrivacySetting {
+    #[serde(rename = "PUBLIC")]
+    Public,
+    #[serde(rename = "PRIVATE")]
+    Private,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolSummonerSummoner {
+    #[serde(rename = "gameName", deserialize_with = "null_as_default")]
+    pub game_name: String,
+    #[serde(rename = "tagLine", deserialize_with = "null_as_default")]
+    pub tag_line: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub puuid: String,
+    #[serde(rename = "summonerLevel", deserialize_with = "null_as_default")]
+    pub summoner_level: u32,
+    #[serde(deserialize_with = "null_as_default")]
+    pub privacy: LolSummonerProfilePrivacySetting,
+    #[serde(rename = "rerollPoints", deserialize_with = "null_as_default")]
+    pub reroll_points: LolSummonerSummonerRerollPoints,
+    #[serde(rename = "tftProfile")]
+    pub tft_profile: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LolSummonerSummonerRerollPoints {
+    #[serde(rename = "currentPoints", deserialize_with = "null_as_default")]
+    pub current_points: u32,
+    #[serde(rename = "numberOfRolls", deserialize_with = "null_as_default")]
+    pub number_of_rolls: u32,
+}
+
+/// `GET /lol-lobby/v2/lobby`
+pub async fn get_lol_lobby_v2_lobby(client: &LCUClient) -> Result<LolLobbyLobby, LcuError> {
+    let path = "/lol-lobby/v2/lobby".to_string();
+    let response = client.request(Method::GET, &path, None).await?;
+    Ok(response.json().await?)
+}
+
+/// `POST /lol-lobby/v2/lobby`
+pub async fn post_lol_lobby_v2_lobby(client: &LCUClient, force: Option<bool>, body: &LolLobbyLobbyChangeGameDto) -> Result<LolLobbyLobby, LcuError> {
+    let path = "/lol-lobby/v2/lobby".to_string();
+    let mut query: Vec<String> = Vec::new();
+    if let Some(value) = force {
+        query.push(format!("force={}", urlencoding::encode(&value.to_string())));
+    }
+    let path = if query.is_empty() { path } else { format!("{}?{}", path, query.join("&")) };
+    let response = client.request(Method::POST, &path, serde_json::to_value(body).ok()).await?;
+    Ok(response.json().await?)
+}
+
+/// `POST /lol-lobby/v2/lobby/invitations`
+pub async fn post_lol_lobby_v2_lobby_invitations(client: &LCUClient, body: &Vec<LolLobbyLobbyInvitationDto>) -> Result<(), LcuError> {
+    let path = "/lol-lobby/v2/lobby/invitations".to_string();
+    client.request(Method::POST, &path, serde_json::to_value(body).ok()).await?;
+    Ok(())
+}
+
+/// `GET /lol-summoner/v1/current-summoner`
+pub async fn get_lol_summoner_v1_current_summoner(client: &LCUClient) -> Result<LolSummonerSummoner, LcuError> {
+    let path = "/lol-summoner/v1/current-summoner".to_string();
+    let response = client.request(Method::GET, &path, None).await?;
+    Ok(response.json().await?)
+}
+
+/// `GET /lol-summoner/v2/summoners/puuid/{puuid}`
+pub async fn get_lol_summoner_v2_summoners_puuid_puuid(client: &LCUClient, puuid: &str) -> Result<LolSummonerSummoner, LcuError> {
+    let path = format!("/lol-summoner/v2/summoners/puuid/{}", urlencoding::encode(&puuid.to_string()));
+    let response = client.request(Method::GET, &path, None).await?;
+    Ok(response.json().await?)
+}
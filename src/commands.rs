@@ -0,0 +1,215 @@
+use crate::cli::{OutputArgs, RawArgs};
+use crate::champ_select::ChampSelectSession;
+use crate::config;
+use crate::lcu_client::{self, LCUClient, LcuError};
+use crate::lobby::{self, position_label, Lobby};
+use crate::utils::{arena_site, multi_search_link};
+use crate::{gameflow, region, summoner};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// The running League Client, or an error for the subcommand to print
+pub async fn connect() -> Result<LCUClient, String> {
+    let info = lcu_client::find_league_client()
+        .await
+        .ok_or("League Client is not running")?;
+    LCUClient::new(&info)
+        .await
+        .map_err(|e| format!("Failed to create client: {}", e))
+}
+
+fn print_json(value: &impl serde::Serialize) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+}
+
+/// `status`; not running is a status too, not an error
+pub async fn status(args: &OutputArgs) -> Result<(), String> {
+    let info = lcu_client::find_league_client().await;
+    let phase = match &info {
+        Some(_) => Some(crate::get_gameflow_state(&connect().await?).await),
+        None => None,
+    };
+
+    if args.json {
+        print_json(&json!({
+            "connected": info.is_some(),
+            "port": info.as_ref().map(|info| info.port),
+            "phase": phase,
+        }));
+        return Ok(());
+    }
+    match (info, phase) {
+        (Some(info), Some(phase)) => println!("Connected on port {}, phase: {}", info.port, phase),
+        _ => println!("League Client is not running"),
+    }
+    Ok(())
+}
+
+/// `accept` and `decline`
+pub async fn ready_check(args: &OutputArgs, accept: bool) -> Result<(), String> {
+    let client = connect().await?;
+    let phase = crate::get_gameflow_state(&client).await;
+    if phase != "ReadyCheck" {
+        return Err(format!("No ready check to answer (phase: {})", phase));
+    }
+    let endpoint = if accept {
+        "/lol-matchmaking/v1/ready-check/accept"
+    } else {
+        "/lol-matchmaking/v1/ready-check/decline"
+    };
+    client
+        .post(endpoint, json!({}))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let answer = if accept { "accepted" } else { "declined" };
+    if args.json {
+        print_json(&json!({ "ready_check": answer }));
+    } else {
+        println!("Ready check {}", answer);
+    }
+    Ok(())
+}
+
+/// `lobby`
+pub async fn lobby(args: &OutputArgs) -> Result<(), String> {
+    let config = config::load_config()?;
+    let client = connect().await?;
+    let team = if crate::get_gameflow_state(&client).await == "ChampSelect" {
+        crate::get_champ_select_team(&client, &client, &config).await.1
+    } else {
+        let party = lobby::get_party_lobby(&client)
+            .await
+            .ok_or("Not in a lobby or champ select")?;
+        lobby::get_party_participants(&client, &party, false).await
+    };
+
+    if args.json {
+        print_json(&team.participants);
+        return Ok(());
+    }
+    for participant in &team.participants {
+        match position_label(&participant.assigned_position) {
+            Some(position) => println!(
+                "{}#{} [{}]",
+                participant.game_name, participant.game_tag, position
+            ),
+            None => println!("{}#{}", participant.game_name, participant.game_tag),
+        }
+    }
+    Ok(())
+}
+
+/// `link`, using the same site and team as the automatic multi-search
+pub async fn link(args: &OutputArgs) -> Result<(), String> {
+    let config = config::load_config()?;
+    let client = connect().await?;
+    if crate::get_gameflow_state(&client).await != "ChampSelect" {
+        return Err("Not in champ select".to_string());
+    }
+    let region_info = region::get_region_info(&client)
+        .await
+        .ok_or("Failed to get the region")?;
+    let gameflow_session = gameflow::get_gameflow_session(&client).await;
+    let provider = crate::resolve_automations(&config, gameflow_session.as_ref()).provider;
+    let (session, team) = crate::get_champ_select_team(&client, &client, &config).await;
+
+    let is_arena = gameflow_session.as_ref().is_some_and(|s| s.is_arena());
+    let link = multi_search(
+        &team,
+        session.as_ref().filter(|_| is_arena),
+        region_info.multi_region(),
+        &provider,
+    )
+    .ok_or("Nobody to search for")?;
+    if args.json {
+        print_json(&json!({ "link": link, "provider": provider }));
+    } else {
+        println!("{}", link);
+    }
+    Ok(())
+}
+
+/// Pass the champ-select session in Arena, where only our duo is searched
+fn multi_search(
+    team: &Lobby,
+    arena_session: Option<&ChampSelectSession>,
+    region: &str,
+    provider: &str,
+) -> Option<String> {
+    let duo;
+    let (team, site) = match arena_session {
+        Some(session) => {
+            duo = lobby::split_arena_duo(team, session).0;
+            (&duo, arena_site(provider))
+        }
+        None => (team, provider),
+    };
+    if team.participants.is_empty() {
+        return None;
+    }
+    Some(multi_search_link(team, region, site))
+}
+
+/// `summoner`
+pub async fn summoner(args: &OutputArgs) -> Result<(), String> {
+    let client = connect().await?;
+    let summoner = summoner::get_current_summoner(&client)
+        .await
+        .ok_or("Failed to get the logged-in account")?;
+    if args.json {
+        print_json(&summoner);
+    } else {
+        println!("{}", summoner.riot_id().as_deref().unwrap_or("(no Riot ID)"));
+        println!("Level {}", summoner.summoner_level);
+        println!("PUUID {}", summoner.puuid);
+    }
+    Ok(())
+}
+
+/// `raw`
+pub async fn raw(args: &RawArgs) -> Result<(), String> {
+    let method = Method::from_bytes(args.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid method {:?}", args.method))?;
+    let body = args
+        .body
+        .as_deref()
+        .map(serde_json::from_str::<Value>)
+        .transpose()
+        .map_err(|e| format!("Invalid JSON body: {}", e))?;
+
+    let client = connect().await?;
+    let path = format!("/{}", args.path.trim_start_matches('/'));
+    // Error responses are printed like any other; the client's message is in the body
+    let (status, text, error) = match client.request(method, &path, body).await {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            (status, response.text().await.map_err(|e| e.to_string())?, None)
+        }
+        Err(e) => match &e {
+            LcuError::Status { status, body, .. } => {
+                (status.as_u16(), body.clone(), Some(e.to_string()))
+            }
+            _ => return Err(e.to_string()),
+        },
+    };
+    let value = serde_json::from_str::<Value>(&text).ok();
+
+    if args.output.json {
+        print_json(&json!({
+            "status": status,
+            "body": value.unwrap_or(Value::String(text)),
+        }));
+    } else {
+        match value {
+            Some(value) => print_json(&value),
+            None if !text.is_empty() => println!("{}", text),
+            None => println!("{}", status),
+        }
+    }
+    // Still fail, so scripts can tell an error response from a success
+    match error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,494 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use crate::events::GameEvent;
+use crate::gameflow::GameflowSession;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+const CONFIG_DIR: &str = "league-reveal-console";
+const CONFIG_FILE: &str = "config.toml";
+const CUSTOM_QUEUE_KEY: &str = "custom";
+
+/// Time to skip the auto-accept (from a notification, the API or the TUI) before it is sent
+const DEFAULT_ACCEPT_DELAY_MS: u64 = 2000;
+/// The client declines a ready check that isn't answered within about 10 seconds
+const MAX_ACCEPT_DELAY_MS: u64 = 8000;
+
+pub const MULTI_PROVIDERS: [&str; 4] = ["opgg", "deeplol", "ugg", "tracker"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub browser: Option<BrowserConfig>,
+    pub multi_search: MultiSearchConfig,
+    pub lobby: LobbyConfig,
+    pub ready_check: ReadyCheckConfig,
+    pub tft: TftConfig,
+    pub api: ApiConfig,
+    pub overlay: OverlayConfig,
+    pub metrics: MetricsConfig,
+    pub logging: LoggingConfig,
+    pub journal: JournalConfig,
+    pub notifications: NotificationsConfig,
+    pub sound: SoundConfig,
+    pub discord: DiscordConfig,
+    /// `[[webhooks]]` entries
+    pub webhooks: Vec<WebhookConfig>,
+    /// Per-queue overrides keyed by queue id (`[queues.420]`), game mode
+    /// (`[queues.ARAM]`) or `[queues.custom]` for custom games
+    pub queues: HashMap<String, QueueConfig>,
+}
+
+impl Config {
+    /// Matching queue overrides, least specific first
+    pub fn queue_overrides(&self, session: &GameflowSession) -> Vec<&QueueConfig> {
+        let mut keys = vec![session.game_mode().to_string()];
+        if session.is_custom_game() {
+            keys.push(CUSTOM_QUEUE_KEY.to_string());
+        }
+        keys.push(session.queue_id().to_string());
+
+        keys.iter().filter_map(|key| self.queues.get(key)).collect()
+    }
+
+    /// Rejects settings that would otherwise only fail once a link is opened
+    fn validate(&self) -> Result<(), String> {
+        for (key, queue) in &self.queues {
+            if let Some(provider) = &queue.provider {
+                if !MULTI_PROVIDERS.contains(&provider.as_str()) {
+                    return Err(format!(
+                        "Invalid provider {:?} for [queues.{}], expected one of: {}",
+                        provider,
+                        key,
+                        MULTI_PROVIDERS.join(", ")
+                    ));
+                }
+            }
+        }
+        if self.ready_check.accept_delay_ms > MAX_ACCEPT_DELAY_MS {
+            return Err(format!(
+                "Invalid [ready_check] accept_delay_ms {}, expected at most {}",
+                self.ready_check.accept_delay_ms, MAX_ACCEPT_DELAY_MS
+            ));
+        }
+        if let Some(quiet_hours) = &self.sound.quiet_hours {
+            quiet_hours.range()?;
+        }
+        if !(0.0..=1.0).contains(&self.sound.volume) {
+            return Err(format!(
+                "Invalid [sound] volume {}, expected 0.0 to 1.0",
+                self.sound.volume
+            ));
+        }
+        if self.discord.enabled && self.discord.client_id.is_none() {
+            return Err("[discord] needs a client_id to be enabled".to_string());
+        }
+        for hook in &self.webhooks {
+            if !hook.url.starts_with("http://") && !hook.url.starts_with("https://") {
+                return Err(format!("Invalid webhook url {:?}", hook.url));
+            }
+            crate::webhooks::check_template(hook)
+                .map_err(|e| format!("Invalid template for webhook {}: {}", hook.url, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Unset values fall back to the global settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConfig {
+    pub auto_accept: Option<bool>,
+    /// Champ-select multi-search (or TFT game links)
+    pub open_multi: Option<bool>,
+    /// Premade lobby multi-search
+    pub open_lobby_multi: Option<bool>,
+    /// Multi-search site for this queue, one of `MULTI_PROVIDERS`
+    pub provider: Option<String>,
+    /// Let an armed dodge leave champ select
+    pub dodge: Option<bool>,
+    /// Pick and ban turn events (sounds, notifications, webhooks)
+    pub turn_alerts: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MultiSearchConfig {
+    /// Leave the local player out of multi-search links
+    pub exclude_self: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LobbyConfig {
+    /// Open a multi-search for the premade when a lobby with other players forms
+    pub open_multi: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadyCheckConfig {
+    /// How long after the ready check pops it is auto-accepted
+    pub accept_delay_ms: u64,
+}
+
+impl Default for ReadyCheckConfig {
+    fn default() -> Self {
+        ReadyCheckConfig {
+            accept_delay_ms: DEFAULT_ACCEPT_DELAY_MS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TftConfig {
+    pub auto_accept: bool,
+    /// Open a profile link for every player when a TFT game starts
+    pub open_links: bool,
+    pub provider: TftProvider,
+}
+
+impl Default for TftConfig {
+    fn default() -> Self {
+        TftConfig {
+            auto_accept: true,
+            open_links: false,
+            provider: TftProvider::Lolchess,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TftProvider {
+    Lolchess,
+    Tactics,
+    Metatft,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Serve the local HTTP/WebSocket API on 127.0.0.1
+    pub enabled: bool,
+    pub port: u16,
+    /// Required from clients; generated and saved next to the config file when unset
+    pub token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig {
+            enabled: false,
+            port: 7421,
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayConfig {
+    /// CSS loaded after the default overlay theme; relative paths are resolved
+    /// against the config directory
+    pub theme: Option<PathBuf>,
+}
+
+impl OverlayConfig {
+    pub fn theme_path(&self) -> Option<PathBuf> {
+        let theme = self.theme.as_ref()?;
+        if theme.is_absolute() {
+            return Some(theme.clone());
+        }
+        Some(config_dir().map(|dir| dir.join(theme)).unwrap_or_else(|| theme.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Serve Prometheus metrics on `listen`
+    pub enabled: bool,
+    pub listen: SocketAddr,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: false,
+            listen: SocketAddr::from((Ipv4Addr::LOCALHOST, 9747)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Filter directives, e.g. `info` or `info,league_reveal_console::lcu_client=debug`.
+    /// `RUST_LOG` takes precedence.
+    pub level: String,
+    pub json: bool,
+    /// Also write logs to a rotating file in the XDG state dir
+    pub file: bool,
+    /// hourly | daily | never
+    pub rotation: String,
+    /// Rotated files to keep
+    pub max_files: usize,
+    /// Log auth tokens and full Riot IDs instead of redacting them
+    pub show_sensitive: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: "info".to_string(),
+            json: false,
+            file: false,
+            rotation: "daily".to_string(),
+            max_files: 7,
+            show_sensitive: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// Keep a local record of champ selects, ready checks, dodges and games
+    pub enabled: bool,
+    /// Records older than this are dropped at startup, 0 keeps everything
+    pub retention_days: u32,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        JournalConfig {
+            enabled: true,
+            retention_days: 90,
+        }
+    }
+}
+
+/// Desktop notifications, only used when built with the `notifications` feature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(not(feature = "notifications"), allow(dead_code))]
+pub struct NotificationsConfig {
+    pub enabled: bool,
+    /// Events that show a notification; `dodged` also covers the dodge countdown
+    pub events: Vec<GameEvent>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        NotificationsConfig {
+            enabled: true,
+            events: vec![
+                GameEvent::ReadyCheck,
+                GameEvent::Accepted,
+                GameEvent::PickTurn,
+                GameEvent::BanTurn,
+                GameEvent::Dodged,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    /// Play a sound on ready check and on our pick/ban turn
+    pub enabled: bool,
+    /// 0.0 to 1.0; `aplay` can't change the volume of audio files
+    pub volume: f32,
+    /// Command that plays a WAV file; the first of pw-play, paplay, aplay or afplay when unset
+    pub player: Option<String>,
+    /// Audio files; the built-in beep when unset. Relative paths are resolved
+    /// against the config directory.
+    pub ready_check: Option<PathBuf>,
+    pub pick_turn: Option<PathBuf>,
+    pub ban_turn: Option<PathBuf>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        SoundConfig {
+            enabled: false,
+            volume: 0.8,
+            player: None,
+            ready_check: None,
+            pick_turn: None,
+            ban_turn: None,
+            quiet_hours: None,
+        }
+    }
+}
+
+impl SoundConfig {
+    pub fn file(&self, event: GameEvent) -> Option<PathBuf> {
+        let file = match event {
+            GameEvent::ReadyCheck => self.ready_check.as_ref(),
+            GameEvent::PickTurn => self.pick_turn.as_ref(),
+            GameEvent::BanTurn => self.ban_turn.as_ref(),
+            _ => None,
+        }?;
+        if file.is_absolute() {
+            return Some(file.clone());
+        }
+        Some(config_dir().map(|dir| dir.join(file)).unwrap_or_else(|| file.clone()))
+    }
+}
+
+/// Local times (`HH:MM`) between which no sound is played; may span midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    pub fn range(&self) -> Result<(NaiveTime, NaiveTime), String> {
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|_| format!("Invalid [sound] quiet hours time {:?}, expected HH:MM", time))
+        };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        match self.range() {
+            Ok((start, end)) if start <= end => start <= time && time < end,
+            Ok((start, end)) => time >= start || time < end,
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    /// Show the queue, phase and elapsed time as Discord Rich Presence
+    pub enabled: bool,
+    /// Application id from the Discord developer portal; its name is shown as the game
+    pub client_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events sent to this hook, all of them when empty
+    #[serde(default)]
+    pub events: Vec<GameEvent>,
+    /// JSON body with `{{field}}` placeholders; the plain event payload when unset
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserConfig {
+    pub executable: String,
+    #[serde(default = "default_browser_args")]
+    pub args: Vec<String>,
+}
+
+fn default_browser_args() -> Vec<String> {
+    vec!["{url}".to_string()]
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_DIR))
+}
+
+pub fn load_config() -> Result<Config, String> {
+    let path = match config_path() {
+        Some(path) => path,
+        None => return Ok(Config::default()),
+    };
+
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: Config = toml::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        let lunch = quiet("12:00", "13:30");
+        assert!(!lunch.contains(at("11:59")));
+        assert!(lunch.contains(at("12:00")));
+        assert!(lunch.contains(at("13:29")));
+        assert!(!lunch.contains(at("13:30")));
+    }
+
+    #[test]
+    fn quiet_hours_spanning_midnight() {
+        let night = quiet("23:00", "07:00");
+        assert!(night.contains(at("23:00")));
+        assert!(night.contains(at("23:59")));
+        assert!(night.contains(at("00:00")));
+        assert!(night.contains(at("06:59")));
+        assert!(!night.contains(at("07:00")));
+        assert!(!night.contains(at("12:00")));
+        assert!(!night.contains(at("22:59")));
+    }
+
+    #[test]
+    fn empty_or_invalid_quiet_hours_contain_nothing() {
+        assert!(!quiet("08:00", "08:00").contains(at("08:00")));
+        assert!(!quiet("late", "07:00").contains(at("03:00")));
+        assert!(quiet("25:00", "07:00").range().is_err());
+    }
+
+    #[test]
+    fn accept_delay_must_leave_time_to_accept() {
+        let mut config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.ready_check.accept_delay_ms, DEFAULT_ACCEPT_DELAY_MS);
+        assert!(config.validate().is_ok());
+
+        config.ready_check.accept_delay_ms = MAX_ACCEPT_DELAY_MS + 1;
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::state::SharedState;
+use std::io::Write;
+use std::sync::OnceLock;
+use tracing_subscriber::fmt::MakeWriter;
+
+static CAPTURE: OnceLock<SharedState> = OnceLock::new();
+
+/// Sends console output to the app state's log instead of stdout, for when a
+/// full-screen UI owns the terminal
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub fn capture_into(state: SharedState) {
+    let _ = CAPTURE.set(state);
+}
+
+pub fn is_captured() -> bool {
+    CAPTURE.get().is_some()
+}
+
+/// Log writer for stdout that respects output capture
+pub struct ConsoleWriter;
+
+impl<'a> MakeWriter<'a> for ConsoleWriter {
+    type Writer = ConsoleEvent;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ConsoleEvent(Vec::new())
+    }
+}
+
+/// One formatted log event, written out when dropped
+pub struct ConsoleEvent(Vec<u8>);
+
+impl Write for ConsoleEvent {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleEvent {
+    fn drop(&mut self) {
+        let text = String::from_utf8_lossy(&self.0);
+        match CAPTURE.get() {
+            Some(state) => {
+                let mut state = state.lock().unwrap();
+                for line in text.lines() {
+                    state.record_log(strip_ansi(line));
+                }
+            }
+            None => {
+                let _ = std::io::stdout().lock().write_all(text.as_bytes());
+            }
+        }
+    }
+}
+
+/// Drops color codes, which the dashboard would show as garbage
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip up to and including the final byte of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
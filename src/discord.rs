@@ -0,0 +1,421 @@
+use crate::config::DiscordConfig;
+use crate::game_data::GameDataCache;
+use crate::gameflow::GameflowSession;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::UnixStream;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+const SOCKET_PREFIX: &str = "discord-ipc-";
+const SOCKET_COUNT: u32 = 10;
+#[cfg(not(test))]
+const RECONNECT_DELAY: Duration = Duration::from_secs(15);
+#[cfg(test)]
+const RECONNECT_DELAY: Duration = Duration::from_millis(50);
+// Discord's own frames are tiny, anything this large is not Discord
+const MAX_FRAME_LEN: u32 = 64 * 1024;
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+const OP_PING: u32 = 3;
+const OP_PONG: u32 = 4;
+
+/// What the status shows; `None` clears it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    pub details: String,
+    pub state: Option<String>,
+    /// Unix seconds the elapsed timer counts from
+    pub start: Option<i64>,
+}
+
+/// Discord Rich Presence over the local IPC socket. Updates go to a background
+/// task that keeps (re)connecting while Discord is closed or restarts.
+pub struct DiscordPresence {
+    /// `None` when disabled
+    activity: Option<watch::Sender<Option<Activity>>>,
+}
+
+impl DiscordPresence {
+    pub fn start(config: &DiscordConfig) -> Self {
+        match (&config.client_id, config.enabled) {
+            (Some(client_id), true) => DiscordPresence::with_socket_dirs(client_id, socket_dirs()),
+            _ => DiscordPresence { activity: None },
+        }
+    }
+
+    fn with_socket_dirs(client_id: &str, dirs: Vec<PathBuf>) -> Self {
+        let (sender, receiver) = watch::channel(None);
+        tokio::spawn(run(client_id.to_string(), dirs, receiver));
+        DiscordPresence {
+            activity: Some(sender),
+        }
+    }
+
+    /// Shows the gameflow phase; phases worth nothing to others clear the status
+    pub fn update(
+        &self,
+        phase: &str,
+        session: Option<&GameflowSession>,
+        game_data: Option<&GameDataCache>,
+    ) {
+        let queue = session.map(|session| {
+            game_data
+                .and_then(|data| data.queue_name(session.queue_id()))
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| session.game_mode().to_string())
+        });
+        let details = match phase {
+            "Lobby" => "In lobby",
+            "Matchmaking" => "In queue",
+            "ReadyCheck" => "Match found",
+            "ChampSelect" => "In champ select",
+            "InProgress" | "Reconnect" => "In game",
+            "WaitingForStats" | "PreEndOfGame" | "EndOfGame" => "Game ended",
+            _ => return self.set(None),
+        };
+        self.set(Some(Activity {
+            details: details.to_string(),
+            state: queue,
+            start: Some(chrono::Utc::now().timestamp()),
+        }));
+    }
+
+    pub fn clear(&self) {
+        self.set(None);
+    }
+
+    fn set(&self, activity: Option<Activity>) {
+        if let Some(sender) = &self.activity {
+            sender.send_replace(activity);
+        }
+    }
+}
+
+/// Where Discord puts its sockets, same order as the official SDK
+fn socket_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var).map(PathBuf::from))
+        .collect();
+    dirs.push(PathBuf::from("/tmp"));
+    // Flatpak and Snap installs put the socket in a subdirectory
+    let sandboxed: Vec<PathBuf> = dirs
+        .iter()
+        .flat_map(|dir| {
+            [
+                dir.join("app/com.discordapp.Discord"),
+                dir.join("snap.discord"),
+            ]
+        })
+        .collect();
+    dirs.extend(sandboxed);
+    dirs
+}
+
+async fn connect(dirs: &[PathBuf]) -> Option<UnixStream> {
+    for dir in dirs {
+        for n in 0..SOCKET_COUNT {
+            let path = dir.join(format!("{}{}", SOCKET_PREFIX, n));
+            if let Ok(stream) = UnixStream::connect(&path).await {
+                debug!("Connected to Discord at {}", path.display());
+                return Some(stream);
+            }
+        }
+    }
+    None
+}
+
+async fn run(
+    client_id: String,
+    dirs: Vec<PathBuf>,
+    mut activity: watch::Receiver<Option<Activity>>,
+) {
+    loop {
+        if let Some(stream) = connect(&dirs).await {
+            match session(&client_id, stream, &mut activity).await {
+                Ok(()) => return,
+                Err(e) => info!("Lost connection to Discord: {}", e),
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+            // Stopped
+            Err(_) = activity.changed() => return,
+        }
+    }
+}
+
+/// One connection: handshake, then send every activity change until it drops.
+/// Returns `Ok` only once the presence is dropped.
+async fn session(
+    client_id: &str,
+    stream: UnixStream,
+    activity: &mut watch::Receiver<Option<Activity>>,
+) -> std::io::Result<()> {
+    let (mut reader, mut writer) = stream.into_split();
+    write_frame(
+        &mut writer,
+        OP_HANDSHAKE,
+        &json!({ "v": 1, "client_id": client_id }),
+    )
+    .await?;
+    let (op, ready) = read_frame(&mut reader).await?;
+    if op != OP_FRAME || ready["evt"] != "READY" {
+        return Err(invalid_data(format!("handshake rejected: {}", ready)));
+    }
+    info!("Connected to Discord");
+
+    // Reading a frame isn't cancel safe, so it gets its own task instead of a select! branch
+    let (frames_tx, mut frames) = mpsc::channel(8);
+    let read_task = tokio::spawn(async move {
+        loop {
+            let frame = read_frame(&mut reader).await;
+            let failed = frame.is_err();
+            if frames_tx.send(frame).await.is_err() || failed {
+                return;
+            }
+        }
+    });
+    let result = send_activities(&mut writer, &mut frames, activity).await;
+    read_task.abort();
+    result
+}
+
+async fn send_activities(
+    writer: &mut OwnedWriteHalf,
+    frames: &mut mpsc::Receiver<std::io::Result<(u32, Value)>>,
+    activity: &mut watch::Receiver<Option<Activity>>,
+) -> std::io::Result<()> {
+    // Whatever was set while disconnected
+    activity.mark_changed();
+    let mut nonce = 0u64;
+    loop {
+        tokio::select! {
+            changed = activity.changed() => {
+                if changed.is_err() {
+                    let _ = write_frame(writer, OP_CLOSE, &json!({})).await;
+                    return Ok(());
+                }
+                let current = activity.borrow_and_update().clone();
+                nonce += 1;
+                write_frame(writer, OP_FRAME, &set_activity(current.as_ref(), nonce)).await?;
+            }
+            frame = frames.recv() => {
+                let (op, payload) = frame.unwrap_or_else(|| Err(invalid_data("connection closed".to_string())))?;
+                match op {
+                    OP_PING => write_frame(writer, OP_PONG, &payload).await?,
+                    OP_CLOSE => return Err(invalid_data(format!("closed by Discord: {}", payload))),
+                    _ if payload["evt"] == "ERROR" => warn!("Discord rejected the status: {}", payload["data"]),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn set_activity(activity: Option<&Activity>, nonce: u64) -> Value {
+    let activity = activity.map(|activity| {
+        let mut value = json!({ "details": activity.details });
+        if let Some(state) = &activity.state {
+            value["state"] = json!(state);
+        }
+        if let Some(start) = activity.start {
+            value["timestamps"] = json!({ "start": start });
+        }
+        value
+    });
+    json!({
+        "cmd": "SET_ACTIVITY",
+        "args": { "pid": std::process::id(), "activity": activity },
+        "nonce": nonce.to_string(),
+    })
+}
+
+/// Frames are a little-endian opcode and length followed by JSON
+async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    op: u32,
+    payload: &Value,
+) -> std::io::Result<()> {
+    let body = payload.to_string();
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&op.to_le_bytes());
+    frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    frame.extend_from_slice(body.as_bytes());
+    writer.write_all(&frame).await
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<(u32, Value)> {
+    let op = reader.read_u32_le().await?;
+    let len = reader.read_u32_le().await?;
+    if len > MAX_FRAME_LEN {
+        return Err(invalid_data(format!("frame of {} bytes", len)));
+    }
+    let mut body = vec![0; len as usize];
+    reader.read_exact(&mut body).await?;
+    let payload = serde_json::from_slice(&body).map_err(|e| invalid_data(e.to_string()))?;
+    Ok((op, payload))
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    /// Stand-in for the Discord client: answers the handshake and forwards
+    /// every frame after it
+    struct FakeDiscord {
+        dir: PathBuf,
+        frames: mpsc::UnboundedReceiver<(u32, Value)>,
+        /// Accepted connections so far
+        connections: watch::Receiver<usize>,
+        /// Drops the current connection
+        hang_up: mpsc::UnboundedSender<()>,
+    }
+
+    impl FakeDiscord {
+        fn start(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "discord-ipc-test-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let listener = UnixListener::bind(dir.join("discord-ipc-0")).unwrap();
+            let (frames_tx, frames) = mpsc::unbounded_channel();
+            let (connections_tx, connections) = watch::channel(0);
+            let (hang_up, mut hang_ups) = mpsc::unbounded_channel::<()>();
+
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let (op, handshake) = read_frame(&mut stream).await.unwrap();
+                    assert_eq!(op, OP_HANDSHAKE);
+                    assert_eq!(handshake["client_id"], "1234");
+                    write_frame(
+                        &mut stream,
+                        OP_FRAME,
+                        &json!({ "cmd": "DISPATCH", "evt": "READY" }),
+                    )
+                    .await
+                    .unwrap();
+                    connections_tx.send_modify(|count| *count += 1);
+                    loop {
+                        tokio::select! {
+                            frame = read_frame(&mut stream) => match frame {
+                                Ok(frame) => frames_tx.send(frame).unwrap(),
+                                Err(_) => break,
+                            },
+                            _ = hang_ups.recv() => break,
+                        }
+                    }
+                }
+            });
+
+            FakeDiscord {
+                dir,
+                frames,
+                connections,
+                hang_up,
+            }
+        }
+
+        async fn next_activity(&mut self) -> Value {
+            let (op, frame) = tokio::time::timeout(Duration::from_secs(5), self.frames.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(op, OP_FRAME);
+            assert_eq!(frame["cmd"], "SET_ACTIVITY");
+            frame["args"]["activity"].clone()
+        }
+    }
+
+    impl Drop for FakeDiscord {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn shows_phase_queue_and_elapsed_time() {
+        let mut discord = FakeDiscord::start("activity");
+        let presence = DiscordPresence::with_socket_dirs("1234", vec![discord.dir.clone()]);
+
+        presence.set(Some(Activity {
+            details: "In queue".to_string(),
+            state: Some("Ranked Solo/Duo".to_string()),
+            start: Some(1_700_000_000),
+        }));
+        let activity = discord.next_activity().await;
+        assert_eq!(activity["details"], "In queue");
+        assert_eq!(activity["state"], "Ranked Solo/Duo");
+        assert_eq!(activity["timestamps"]["start"], 1_700_000_000);
+
+        presence.clear();
+        assert!(discord.next_activity().await.is_null());
+    }
+
+    #[tokio::test]
+    async fn phases_without_a_status_clear_it() {
+        let mut discord = FakeDiscord::start("phases");
+        let presence = DiscordPresence::with_socket_dirs("1234", vec![discord.dir.clone()]);
+
+        presence.update("ChampSelect", None, None);
+        let activity = discord.next_activity().await;
+        assert_eq!(activity["details"], "In champ select");
+        assert!(activity["timestamps"]["start"].is_i64());
+
+        presence.update("None", None, None);
+        assert!(discord.next_activity().await.is_null());
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_resends_the_current_status() {
+        let mut discord = FakeDiscord::start("reconnect");
+        let presence = DiscordPresence::with_socket_dirs("1234", vec![discord.dir.clone()]);
+        presence.update("InProgress", None, None);
+        assert_eq!(discord.next_activity().await["details"], "In game");
+
+        discord.hang_up.send(()).unwrap();
+        discord
+            .connections
+            .wait_for(|count| *count == 2)
+            .await
+            .unwrap();
+
+        assert_eq!(discord.next_activity().await["details"], "In game");
+    }
+
+    #[tokio::test]
+    async fn answers_pings() {
+        let (mut client, server) = UnixStream::pair().unwrap();
+        let (_sender, mut receiver) = watch::channel(None);
+        tokio::spawn(async move { session("1234", server, &mut receiver).await });
+
+        let (op, _) = read_frame(&mut client).await.unwrap();
+        assert_eq!(op, OP_HANDSHAKE);
+        write_frame(&mut client, OP_FRAME, &json!({ "evt": "READY" }))
+            .await
+            .unwrap();
+        // The initial (empty) status
+        read_frame(&mut client).await.unwrap();
+        write_frame(&mut client, OP_PING, &json!({ "n": 1 }))
+            .await
+            .unwrap();
+
+        let (op, payload) = read_frame(&mut client).await.unwrap();
+        assert_eq!(op, OP_PONG);
+        assert_eq!(payload["n"], 1);
+    }
+}
@@ -0,0 +1,703 @@
+use crate::champ_select::ChampSelectSession;
+use crate::gameflow::GameflowSession;
+use crate::lcu_client::{LCUClient, LcuError};
+use crate::lobby::{Lobby, PartyLobby};
+use crate::region::RegionInfo;
+use crate::summoner::Summoner;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Enough for a champ select where every cell has a few problems
+const MAX_REPAIRS: usize = 200;
+
+#[derive(Debug, clap::Args)]
+pub struct DriftArgs {
+    /// Write the report to this file instead of stdout
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+    /// Also save each response as <dir>/<endpoint>.json (contains account data)
+    #[arg(long)]
+    pub save_payloads: Option<PathBuf>,
+}
+
+type Check = fn(&Value) -> Vec<Finding>;
+
+/// Every GET endpoint the app deserializes into a model, with that model.
+/// Endpoints read as plain JSON (game data assets, Riot ID lookups) aren't listed.
+const ENDPOINTS: [(&str, &str, Check); 8] = [
+    (
+        "/lol-summoner/v1/current-summoner",
+        "Summoner",
+        check::<Summoner>,
+    ),
+    (
+        "/riotclient/region-locale",
+        "RegionInfo",
+        check::<RegionInfo>,
+    ),
+    ("/lol-gameflow/v1/gameflow-phase", "String", check::<String>),
+    ("/lol-patch/v1/game-version", "String", check::<String>),
+    (
+        "/lol-gameflow/v1/session",
+        "GameflowSession",
+        check::<GameflowSession>,
+    ),
+    ("/lol-lobby/v2/lobby", "PartyLobby", check::<PartyLobby>),
+    ("/chat/v5/participants", "Lobby", check::<Lobby>),
+    (
+        "/lol-champ-select/v1/session",
+        "ChampSelectSession",
+        check::<ChampSelectSession>,
+    ),
+];
+
+/// Where in a payload, e.g. `myTeam[3].nameVisibilityType`
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, PartialEq)]
+enum Finding {
+    /// Required by the model but not in the payload; parsing fails
+    MissingField(String),
+    /// A string the model's enum has no variant for; parsing fails
+    UnknownVariant {
+        path: String,
+        value: String,
+        expected: Vec<String>,
+    },
+    /// Present with a type the model can't take; parsing fails
+    InvalidType { path: String, message: String },
+    /// In the payload but not in the model, so it's ignored
+    NewField { path: String, kind: &'static str },
+    /// Optional in the model and not in the payload, so the default is used
+    Defaulted(String),
+}
+
+impl Finding {
+    fn breaks_parsing(&self) -> bool {
+        matches!(
+            self,
+            Finding::MissingField(_) | Finding::UnknownVariant { .. } | Finding::InvalidType { .. }
+        )
+    }
+}
+
+enum Outcome {
+    Checked(Vec<Finding>),
+    /// The endpoint answered with an error, usually because it only exists in some phases
+    Unavailable(String),
+    Failed(String),
+}
+
+/// `schema-drift` subcommand
+pub async fn run(args: &DriftArgs) -> Result<(), String> {
+    let client = crate::commands::connect().await?;
+    if let Some(dir) = &args.save_payloads {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let mut results = Vec::new();
+    for (endpoint, model, check) in ENDPOINTS {
+        let outcome = match fetch(&client, endpoint).await {
+            Ok(payload) => {
+                if let Some(dir) = &args.save_payloads {
+                    save_payload(dir, endpoint, &payload)?;
+                }
+                Outcome::Checked(check(&payload))
+            }
+            Err(LcuError::Status { status, .. }) => Outcome::Unavailable(status.to_string()),
+            Err(e) => Outcome::Failed(e.to_string()),
+        };
+        results.push((endpoint, model, outcome));
+    }
+
+    let report = report(&client, &results).await;
+    match &args.out {
+        Some(out) => {
+            std::fs::write(out, &report)
+                .map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+            println!("Report written to {}", out.display());
+        }
+        None => print!("{}", report),
+    }
+
+    let broken = results
+        .iter()
+        .filter(|(_, _, outcome)| match outcome {
+            Outcome::Checked(findings) => findings.iter().any(Finding::breaks_parsing),
+            _ => false,
+        })
+        .count();
+    if broken > 0 {
+        return Err(format!(
+            "{} endpoint(s) no longer match their model",
+            broken
+        ));
+    }
+    Ok(())
+}
+
+async fn fetch(client: &LCUClient, endpoint: &str) -> Result<Value, LcuError> {
+    let response = client.get(endpoint).await?;
+    Ok(response.json().await?)
+}
+
+fn save_payload(dir: &std::path::Path, endpoint: &str, payload: &Value) -> Result<(), String> {
+    let name = endpoint.trim_start_matches('/').replace('/', "_");
+    let path = dir.join(format!("{}.json", name));
+    let text = serde_json::to_string_pretty(payload).unwrap_or_default();
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Deserializes `payload` into `T`, patching each failure so the rest of the
+/// payload is still checked, then compares the model's own serialization with
+/// the payload to find fields it drops or fills in.
+fn check<T: DeserializeOwned + Serialize>(payload: &Value) -> Vec<Finding> {
+    let mut value = payload.clone();
+    let mut findings = Vec::new();
+    // Where stand-ins were put in; errors inside them are ours, not the client's
+    let mut patched: Vec<Vec<Segment>> = Vec::new();
+
+    for _ in 0..MAX_REPAIRS {
+        let (path, message) = match parse::<T>(&value) {
+            Ok(model) => {
+                let parsed = serde_json::to_value(&model).unwrap_or(Value::Null);
+                compare(&value, &parsed, &mut Vec::new(), &mut findings);
+                break;
+            }
+            Err(error) => error,
+        };
+        let target = location(&path, &message);
+
+        let (finding, candidates) = if missing_field(&message).is_some() {
+            (Finding::MissingField(display(&target)), placeholders())
+        } else if let Some((variant, expected)) = unknown_variant(&message) {
+            let candidates = expected.iter().map(|v| Value::String(v.clone())).collect();
+            let finding = Finding::UnknownVariant {
+                path: display(&target),
+                value: variant,
+                expected,
+            };
+            (finding, candidates)
+        } else {
+            let finding = Finding::InvalidType {
+                path: display(&target),
+                message: message.clone(),
+            };
+            (finding, placeholders())
+        };
+        if !patched.iter().any(|prefix| target.starts_with(prefix)) {
+            push_once(&mut findings, finding);
+        }
+
+        // Try stand-ins until the parse gets past this spot; `{}` for a missing
+        // object moves the error inside it, which also counts
+        let repaired = candidates.into_iter().find_map(|candidate| {
+            let mut attempt = value.clone();
+            if !set(&mut attempt, &target, candidate) {
+                return None;
+            }
+            match parse::<T>(&attempt) {
+                Err((next_path, next_message)) if location(&next_path, &next_message) == target => {
+                    None
+                }
+                _ => Some(attempt),
+            }
+        });
+        match repaired {
+            Some(attempt) => {
+                value = attempt;
+                patched.push(target);
+            }
+            None => break,
+        }
+    }
+    findings
+}
+
+/// The value an error is about: the field itself for a missing field
+fn location(path: &[Segment], message: &str) -> Vec<Segment> {
+    let mut location = path.to_vec();
+    if let Some(field) = missing_field(message) {
+        location.push(Segment::Key(field.to_string()));
+    }
+    location
+}
+
+fn parse<T: DeserializeOwned>(value: &Value) -> Result<T, (Vec<Segment>, String)> {
+    serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = error
+            .path()
+            .iter()
+            .filter_map(|segment| match segment {
+                serde_path_to_error::Segment::Seq { index } => Some(Segment::Index(*index)),
+                serde_path_to_error::Segment::Map { key } => Some(Segment::Key(key.clone())),
+                _ => None,
+            })
+            .collect();
+        (path, error.inner().to_string())
+    })
+}
+
+fn placeholders() -> Vec<Value> {
+    vec![
+        Value::Null,
+        Value::from(0),
+        Value::Bool(false),
+        Value::String(String::new()),
+        Value::Array(Vec::new()),
+        Value::Object(Default::default()),
+    ]
+}
+
+/// serde_json's "missing field `gameName`"
+fn missing_field(message: &str) -> Option<&str> {
+    message.strip_prefix("missing field `")?.split('`').next()
+}
+
+/// serde_json's "unknown variant `X`, expected one of `A`, `B`" (or "expected `A` or `B`")
+fn unknown_variant(message: &str) -> Option<(String, Vec<String>)> {
+    let rest = message.strip_prefix("unknown variant `")?;
+    let (variant, expected) = rest.split_once('`')?;
+    let expected = expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|value| value.to_string())
+        .collect();
+    Some((variant.to_string(), expected))
+}
+
+fn set(value: &mut Value, path: &[Segment], new: Value) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        *value = new;
+        return true;
+    };
+    let mut current = value;
+    for segment in parents {
+        current = match (segment, current) {
+            (Segment::Key(key), Value::Object(map)) => match map.get_mut(key) {
+                Some(next) => next,
+                None => return false,
+            },
+            (Segment::Index(index), Value::Array(items)) => match items.get_mut(*index) {
+                Some(next) => next,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+    match (last, current) {
+        (Segment::Key(key), Value::Object(map)) => {
+            map.insert(key.clone(), new);
+            true
+        }
+        (Segment::Index(index), Value::Array(items)) if *index < items.len() => {
+            items[*index] = new;
+            true
+        }
+        _ => false,
+    }
+}
+
+fn compare(payload: &Value, parsed: &Value, path: &mut Vec<Segment>, findings: &mut Vec<Finding>) {
+    match (payload, parsed) {
+        (Value::Object(payload), Value::Object(parsed)) => {
+            for (key, value) in payload {
+                path.push(Segment::Key(key.clone()));
+                match parsed.get(key) {
+                    Some(parsed) => compare(value, parsed, path, findings),
+                    None => push_once(
+                        findings,
+                        Finding::NewField {
+                            path: display(path),
+                            kind: kind(value),
+                        },
+                    ),
+                }
+                path.pop();
+            }
+            for key in parsed.keys().filter(|key| !payload.contains_key(*key)) {
+                path.push(Segment::Key(key.clone()));
+                push_once(findings, Finding::Defaulted(display(path)));
+                path.pop();
+            }
+        }
+        (Value::Array(payload), Value::Array(parsed)) => {
+            for (index, (value, parsed)) in payload.iter().zip(parsed).enumerate() {
+                path.push(Segment::Index(index));
+                compare(value, parsed, path, findings);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Array items report once for the whole array
+fn push_once(findings: &mut Vec<Finding>, finding: Finding) {
+    if !findings.contains(&finding) {
+        findings.push(finding);
+    }
+}
+
+/// Paths with indexes collapsed, so every cell of a team reports as one line
+fn display(path: &[Segment]) -> String {
+    let mut text = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if text.is_empty() => text.push_str(key),
+            Segment::Key(key) => {
+                text.push('.');
+                text.push_str(key);
+            }
+            Segment::Index(_) => text.push_str("[]"),
+        }
+    }
+    if text.is_empty() {
+        text.push_str("(root)");
+    }
+    text
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Markdown, ready to paste into an issue
+async fn report(client: &LCUClient, results: &[(&str, &str, Outcome)]) -> String {
+    let text = |endpoint: &'static str| async move {
+        match fetch(client, endpoint).await {
+            Ok(Value::String(text)) => text,
+            _ => "unknown".to_string(),
+        }
+    };
+    let client_version = text("/lol-patch/v1/game-version").await;
+    let phase = text("/lol-gameflow/v1/gameflow-phase").await;
+    let region = match crate::region::get_region_info(client).await {
+        Some(info) => format!("{} ({})", info.region, info.locale),
+        None => "unknown".to_string(),
+    };
+
+    let mut out = String::new();
+    out.push_str("# LCU schema drift report\n\n");
+    let _ = writeln!(out, "- league-reveal-console {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "- Client version: {}", client_version);
+    let _ = writeln!(out, "- Region: {}", region);
+    let _ = writeln!(out, "- Gameflow phase: {}", phase);
+    let _ = writeln!(
+        out,
+        "- Checked: {}",
+        chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")
+    );
+
+    out.push_str("\n| Endpoint | Model | Result |\n|---|---|---|\n");
+    for (endpoint, model, outcome) in results {
+        let result = match outcome {
+            Outcome::Checked(findings) => summary(findings),
+            Outcome::Unavailable(status) => format!("not available in this phase ({})", status),
+            Outcome::Failed(e) => format!("request failed: {}", e),
+        };
+        let _ = writeln!(out, "| `GET {}` | `{}` | {} |", endpoint, model, result);
+    }
+
+    for (endpoint, model, outcome) in results {
+        let Outcome::Checked(findings) = outcome else {
+            continue;
+        };
+        if findings.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "\n## `GET {}` (`{}`)", endpoint, model);
+        section(
+            &mut out,
+            "Missing required fields (parsing fails)",
+            findings,
+            |f| match f {
+                Finding::MissingField(path) => Some(format!("`{}`", path)),
+                _ => None,
+            },
+        );
+        section(
+            &mut out,
+            "Enum values not covered (parsing fails)",
+            findings,
+            |f| match f {
+                Finding::UnknownVariant {
+                    path,
+                    value,
+                    expected,
+                } => Some(format!(
+                    "`{}`: `{}` (known: {})",
+                    path,
+                    value,
+                    expected
+                        .iter()
+                        .map(|v| format!("`{}`", v))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+                _ => None,
+            },
+        );
+        section(
+            &mut out,
+            "Type mismatches (parsing fails)",
+            findings,
+            |f| match f {
+                Finding::InvalidType { path, message } => Some(format!("`{}`: {}", path, message)),
+                _ => None,
+            },
+        );
+        section(
+            &mut out,
+            "New fields (ignored by the model)",
+            findings,
+            |f| match f {
+                Finding::NewField { path, kind } => Some(format!("`{}` ({})", path, kind)),
+                _ => None,
+            },
+        );
+        section(
+            &mut out,
+            "Absent fields (model default used)",
+            findings,
+            |f| match f {
+                Finding::Defaulted(path) => Some(format!("`{}`", path)),
+                _ => None,
+            },
+        );
+    }
+    out
+}
+
+fn summary(findings: &[Finding]) -> String {
+    let count = |matches: fn(&Finding) -> bool| findings.iter().filter(|f| matches(f)).count();
+    let parts = [
+        (
+            count(|f| matches!(f, Finding::MissingField(_))),
+            "missing fields",
+        ),
+        (
+            count(|f| matches!(f, Finding::UnknownVariant { .. })),
+            "unknown enum values",
+        ),
+        (
+            count(|f| matches!(f, Finding::InvalidType { .. })),
+            "type mismatches",
+        ),
+        (
+            count(|f| matches!(f, Finding::NewField { .. })),
+            "new fields",
+        ),
+        (
+            count(|f| matches!(f, Finding::Defaulted(_))),
+            "defaulted fields",
+        ),
+    ];
+    let parts: Vec<String> = parts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{}: {}", label, n))
+        .collect();
+    match (
+        findings.iter().any(Finding::breaks_parsing),
+        parts.is_empty(),
+    ) {
+        (_, true) => "ok".to_string(),
+        (true, false) => format!("**fails to parse**: {}", parts.join(", ")),
+        (false, false) => format!("parses: {}", parts.join(", ")),
+    }
+}
+
+fn section(
+    out: &mut String,
+    title: &str,
+    findings: &[Finding],
+    line: impl Fn(&Finding) -> Option<String>,
+) {
+    let lines: Vec<String> = findings.iter().filter_map(line).collect();
+    if lines.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "\n**{}**\n", title);
+    for line in lines {
+        let _ = writeln!(out, "- {}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CURRENT_SUMMONER: [&str; 4] = [
+        include_str!("../tests/fixtures/lcu/na/lol-summoner_v1_current-summoner.json"),
+        include_str!("../tests/fixtures/lcu/euw/lol-summoner_v1_current-summoner.json"),
+        include_str!("../tests/fixtures/lcu/kr/lol-summoner_v1_current-summoner.json"),
+        include_str!("../tests/fixtures/lcu/sg2/lol-summoner_v1_current-summoner.json"),
+    ];
+
+    fn summoner() -> Value {
+        serde_json::from_str(CURRENT_SUMMONER[1]).unwrap()
+    }
+
+    /// A champ select with only the fields the model requires, one cell per side
+    fn session() -> Value {
+        let cell = json!({
+            "assignedPosition": "middle",
+            "cellId": 2,
+            "championId": 0,
+            "championPickIntent": 0,
+            "nameVisibilityType": "HIDDEN",
+            "puuid": "",
+            "spell1Id": 4,
+            "spell2Id": 14,
+            "team": 1
+        });
+        json!({
+            "actions": [[{
+                "actorCellId": 2,
+                "championId": 0,
+                "completed": false,
+                "isAllyAction": true,
+                "isInProgress": true,
+                "type": "pick"
+            }]],
+            "localPlayerCellId": 2,
+            "myTeam": [cell.clone(), cell.clone()],
+            "theirTeam": [cell],
+            "timer": {
+                "adjustedTimeLeftInPhase": 30000,
+                "isInfinite": false,
+                "phase": "BAN_PICK"
+            }
+        })
+    }
+
+    #[test]
+    fn fixtures_parse() {
+        for payload in CURRENT_SUMMONER {
+            let payload: Value = serde_json::from_str(payload).unwrap();
+            let findings = check::<Summoner>(&payload);
+            assert!(
+                !findings.iter().any(Finding::breaks_parsing),
+                "{:?}",
+                findings
+            );
+        }
+        let euw: Value = serde_json::from_str(CURRENT_SUMMONER[1]).unwrap();
+        assert_eq!(check::<Summoner>(&euw), Vec::new());
+    }
+
+    #[test]
+    fn reports_new_summoner_fields() {
+        let mut payload = summoner();
+        payload["riotIdVerified"] = json!(true);
+        payload["rerollPoints"]["bonusRolls"] = json!(1);
+
+        let findings = check::<Summoner>(&payload);
+        assert_eq!(
+            findings,
+            [
+                Finding::NewField {
+                    path: "rerollPoints.bonusRolls".to_string(),
+                    kind: "number",
+                },
+                Finding::NewField {
+                    path: "riotIdVerified".to_string(),
+                    kind: "bool",
+                },
+            ]
+        );
+
+        // kr has `isSelf`
+        let kr: Value = serde_json::from_str(CURRENT_SUMMONER[2]).unwrap();
+        assert!(check::<Summoner>(&kr).contains(&Finding::NewField {
+            path: "isSelf".to_string(),
+            kind: "bool",
+        }));
+    }
+
+    #[test]
+    fn reports_defaulted_and_invalid_summoner_fields() {
+        let mut payload = summoner();
+        payload.as_object_mut().unwrap().remove("accountId");
+        payload["summonerLevel"] = json!("62");
+
+        let findings = check::<Summoner>(&payload);
+        assert!(findings.contains(&Finding::Defaulted("accountId".to_string())));
+        assert!(findings.iter().any(|finding| matches!(
+            finding,
+            Finding::InvalidType { path, .. } if path == "summonerLevel"
+        )));
+    }
+
+    #[test]
+    fn reports_missing_fields_once_per_array() {
+        let mut payload = session();
+        payload["timer"].as_object_mut().unwrap().remove("phase");
+        for cell in payload["myTeam"].as_array_mut().unwrap() {
+            cell.as_object_mut().unwrap().remove("puuid");
+        }
+
+        let findings = check::<ChampSelectSession>(&payload);
+        let missing: Vec<_> = findings
+            .iter()
+            .filter(|finding| matches!(finding, Finding::MissingField(_)))
+            .collect();
+        assert_eq!(
+            missing,
+            [
+                &Finding::MissingField("myTeam[].puuid".to_string()),
+                &Finding::MissingField("timer.phase".to_string()),
+            ]
+        );
+        assert!(findings.iter().any(Finding::breaks_parsing));
+    }
+
+    #[test]
+    fn reports_unknown_name_visibility() {
+        let mut payload = session();
+        payload["theirTeam"][0]["nameVisibilityType"] = json!("VISIBLE");
+
+        let findings = check::<ChampSelectSession>(&payload);
+        assert!(findings.contains(&Finding::UnknownVariant {
+            path: "theirTeam[].nameVisibilityType".to_string(),
+            value: "VISIBLE".to_string(),
+            expected: vec!["HIDDEN".to_string(), "UNHIDDEN".to_string()],
+        }));
+        assert!(findings.iter().any(Finding::breaks_parsing));
+    }
+
+    #[test]
+    fn reports_new_and_defaulted_champ_select_fields() {
+        let mut payload = session();
+        for cell in payload["myTeam"].as_array_mut().unwrap() {
+            cell["gameName"] = json!("Quiet River");
+        }
+
+        let findings = check::<ChampSelectSession>(&payload);
+        assert!(findings.contains(&Finding::NewField {
+            path: "myTeam[].gameName".to_string(),
+            kind: "string",
+        }));
+        assert!(findings.contains(&Finding::Defaulted("myTeam[].wardSkinId".to_string())));
+        assert!(findings.contains(&Finding::Defaulted("allowRerolling".to_string())));
+        assert!(!findings.iter().any(Finding::breaks_parsing));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::game_data::GameDataCache;
+use crate::gameflow::GameflowSession;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Client events that the journal and outgoing integrations react to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEvent {
+    ReadyCheck,
+    Accepted,
+    ChampSelectStarted,
+    /// Our pick is in progress
+    PickTurn,
+    /// Our ban is in progress
+    BanTurn,
+    Dodged,
+    GameStarted,
+    GameEnded,
+}
+
+/// What happened and in which game. Never carries other players' identities.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPayload {
+    pub event: GameEvent,
+    pub at: DateTime<Utc>,
+    pub queue_id: Option<i64>,
+    pub queue: Option<String>,
+    pub game_mode: Option<String>,
+    pub game_id: Option<u64>,
+    pub region: Option<String>,
+    /// Game length, for `game_ended`
+    pub duration_secs: Option<u64>,
+}
+
+impl EventPayload {
+    pub fn new(
+        event: GameEvent,
+        session: Option<&GameflowSession>,
+        game_data: Option<&GameDataCache>,
+        region: Option<&str>,
+        duration: Option<Duration>,
+    ) -> Self {
+        EventPayload {
+            event,
+            at: Utc::now(),
+            queue_id: session.map(|s| s.queue_id()),
+            queue: session.and_then(|s| {
+                game_data
+                    .and_then(|data| data.queue_name(s.queue_id()))
+                    .map(|name| name.to_string())
+            }),
+            game_mode: session.map(|s| s.game_mode().to_string()),
+            game_id: session.map(|s| s.game_data.game_id).filter(|id| *id != 0),
+            region: region.map(|region| region.to_string()),
+            duration_secs: duration.map(|d| d.as_secs()),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use crate::lcu_client::LCUClient;
+use tracing::{info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const CACHE_DIR: &str = "league-reveal-console";
+const CACHE_FILE_PREFIX: &str = "game-data-";
+
+/// Champion, summoner spell, queue and map names for one client version
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GameDataCache {
+    pub version: String,
+    pub champions: HashMap<i64, String>,
+    pub summoner_spells: HashMap<i64, String>,
+    pub queues: HashMap<i64, String>,
+    pub maps: HashMap<i64, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedAsset {
+    id: i64,
+    name: String,
+}
+
+impl GameDataCache {
+    pub fn champion_name(&self, id: i64) -> Option<&str> {
+        self.champions.get(&id).map(|name| name.as_str())
+    }
+
+    pub fn spell_name(&self, id: i64) -> Option<&str> {
+        self.summoner_spells.get(&id).map(|name| name.as_str())
+    }
+
+    pub fn queue_name(&self, id: i64) -> Option<&str> {
+        self.queues.get(&id).map(|name| name.as_str())
+    }
+
+    pub fn map_name(&self, id: i64) -> Option<&str> {
+        self.maps.get(&id).map(|name| name.as_str())
+    }
+}
+
+/// Makes sure `cache` matches the running client's patch, loading it from disk
+/// or the LCU when the version changed. Keeps the old data if the refresh fails.
+pub async fn refresh(cache: &mut Option<GameDataCache>, remoting_client: &LCUClient) {
+    let version = match get_client_version(remoting_client).await {
+        Some(version) => version,
+        None => return,
+    };
+    if cache.as_ref().is_some_and(|c| c.version == version) {
+        return;
+    }
+
+    if let Some(data) = load_from_disk(&version) {
+        *cache = Some(data);
+        return;
+    }
+
+    match fetch(remoting_client, &version).await {
+        Some(data) => {
+            info!("Loaded game data for patch {}", version);
+            if let Err(e) = save_to_disk(&data) {
+                warn!("Failed to save game data cache: {}", e);
+            }
+            *cache = Some(data);
+        }
+        None => warn!("Failed to load game data for patch {}", version),
+    }
+}
+
+async fn get_client_version(remoting_client: &LCUClient) -> Option<String> {
+    let response = remoting_client
+        .get("/lol-patch/v1/game-version")
+        .await
+        .ok()?;
+    let version: String = response.json().await.ok()?;
+    if version.is_empty() {
+        return None;
+    }
+    Some(version)
+}
+
+async fn fetch(remoting_client: &LCUClient, version: &str) -> Option<GameDataCache> {
+    let champions = fetch_names(remoting_client, "champion-summary.json").await?;
+    let summoner_spells = fetch_names(remoting_client, "summoner-spells.json").await?;
+    let queues = fetch_names(remoting_client, "queues.json").await?;
+    let maps = fetch_names(remoting_client, "maps.json").await?;
+
+    Some(GameDataCache {
+        version: version.to_string(),
+        champions,
+        summoner_spells,
+        queues,
+        maps,
+    })
+}
+
+async fn fetch_names(remoting_client: &LCUClient, asset: &str) -> Option<HashMap<i64, String>> {
+    let response = remoting_client
+        .get(&format!("/lol-game-data/assets/v1/{}", asset))
+        .await
+        .ok()?;
+    let body: serde_json::Value = response.json().await.ok()?;
+
+    // Most assets are arrays, but queues.json is an object keyed by queue id
+    let entries: Vec<serde_json::Value> = match body {
+        serde_json::Value::Array(entries) => entries,
+        serde_json::Value::Object(entries) => entries
+            .into_iter()
+            .map(|(id, mut entry)| {
+                if entry.get("id").is_none() {
+                    entry["id"] = serde_json::json!(id.parse::<i64>().unwrap_or_default());
+                }
+                entry
+            })
+            .collect(),
+        _ => return None,
+    };
+
+    Some(
+        entries
+            .into_iter()
+            .filter_map(|entry| serde_json::from_value::<NamedAsset>(entry).ok())
+            .map(|asset| (asset.id, asset.name))
+            .collect(),
+    )
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(CACHE_DIR))
+}
+
+fn cache_file(version: &str) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(format!("{}{}.json", CACHE_FILE_PREFIX, version)))
+}
+
+fn load_from_disk(version: &str) -> Option<GameDataCache> {
+    let contents = std::fs::read_to_string(cache_file(version)?).ok()?;
+    let data: GameDataCache = serde_json::from_str(&contents).ok()?;
+    (data.version == version).then_some(data)
+}
+
+fn save_to_disk(data: &GameDataCache) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = cache_dir().ok_or("no cache directory")?;
+    std::fs::create_dir_all(&dir)?;
+
+    // Only the current patch is worth keeping
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(CACHE_FILE_PREFIX) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+
+    let path = dir.join(format!("{}{}.json", CACHE_FILE_PREFIX, data.version));
+    std::fs::write(path, serde_json::to_string(data)?)?;
+    Ok(())
+}
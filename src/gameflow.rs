@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use crate::lcu_client::LCUClient;
+use tracing::warn;
+
+const TFT_MAP_ID: i64 = 22;
+const ARENA_GAME_MODE: &str = "CHERRY";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameflowSession {
+    pub game_data: GameData,
+    #[serde(default)]
+    pub map: Map,
+    pub phase: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameData {
+    pub game_id: u64,
+    pub is_custom_game: bool,
+    pub queue: Queue,
+    #[serde(default)]
+    pub team_one: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub team_two: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Queue {
+    pub id: i64,
+    #[serde(default)]
+    pub description: String,
+    pub game_mode: String,
+    #[serde(default)]
+    pub is_ranked: bool,
+    pub map_id: i64,
+    #[serde(rename = "type")]
+    pub queue_type: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Map {
+    pub game_mode: String,
+    pub id: i64,
+}
+
+impl GameflowSession {
+    pub fn queue_id(&self) -> i64 {
+        self.game_data.queue.id
+    }
+
+    /// Custom games carry no queue, so prefer the map the session reports
+    pub fn map_id(&self) -> i64 {
+        if self.map.id != 0 {
+            self.map.id
+        } else {
+            self.game_data.queue.map_id
+        }
+    }
+
+    pub fn game_mode(&self) -> &str {
+        if !self.map.game_mode.is_empty() {
+            &self.map.game_mode
+        } else {
+            &self.game_data.queue.game_mode
+        }
+    }
+
+    pub fn is_custom_game(&self) -> bool {
+        self.game_data.is_custom_game
+    }
+
+    pub fn is_tft(&self) -> bool {
+        self.game_mode() == "TFT" || self.map_id() == TFT_MAP_ID
+    }
+
+    pub fn is_arena(&self) -> bool {
+        self.game_mode() == ARENA_GAME_MODE
+    }
+
+    /// Puuids of everyone in the current game, as listed by the gameflow session
+    pub fn player_puuids(&self) -> Vec<String> {
+        self.game_data
+            .team_one
+            .iter()
+            .chain(self.game_data.team_two.iter())
+            .filter_map(|player| player["puuid"].as_str())
+            .filter(|puuid| !puuid.is_empty())
+            .map(|puuid| puuid.to_string())
+            .collect()
+    }
+}
+
+pub async fn get_gameflow_session(remoting_client: &LCUClient) -> Option<GameflowSession> {
+    let response = remoting_client
+        .get("/lol-gameflow/v1/session")
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+
+    match response.json().await {
+        Ok(session) => Some(session),
+        Err(e) => {
+            warn!("Failed to parse gameflow session: {:?}", e);
+            None
+        }
+    }
+}
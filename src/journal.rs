@@ -0,0 +1,240 @@
+use crate::config::JournalConfig;
+use crate::events::{EventPayload, GameEvent};
+use crate::summoner::Summoner;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use tracing::warn;
+
+const JOURNAL_DIR: &str = "league-reveal-console";
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalEvent {
+    ChampSelectStarted,
+    ReadyCheck,
+    Accepted,
+    Dodged,
+    GameEnded,
+}
+
+impl JournalEvent {
+    /// The events worth keeping; turns in champ select are too fine-grained
+    fn from_game_event(event: GameEvent) -> Option<Self> {
+        match event {
+            GameEvent::ChampSelectStarted => Some(JournalEvent::ChampSelectStarted),
+            GameEvent::ReadyCheck => Some(JournalEvent::ReadyCheck),
+            GameEvent::Accepted => Some(JournalEvent::Accepted),
+            GameEvent::Dodged => Some(JournalEvent::Dodged),
+            GameEvent::GameEnded => Some(JournalEvent::GameEnded),
+            GameEvent::PickTurn | GameEvent::BanTurn | GameEvent::GameStarted => None,
+        }
+    }
+}
+
+/// One line of the journal. Only our own account is recorded, never other players.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub at: DateTime<Utc>,
+    pub event: JournalEvent,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub puuid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub riot_id: Option<String>,
+    /// Game length, for `game_ended`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct JournalArgs {
+    /// Only show this kind of event
+    #[arg(long, value_enum)]
+    pub event: Option<JournalEvent>,
+    /// Only show events from the last N days
+    #[arg(long)]
+    pub days: Option<i64>,
+    /// Show at most N of the most recent events
+    #[arg(long, default_value_t = 50)]
+    pub limit: usize,
+    /// Print raw JSON lines
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Append-only local record of queue activity
+pub struct Journal {
+    /// `None` when the journal is disabled or there is nowhere to keep it
+    path: Option<PathBuf>,
+    puuid: Option<String>,
+    riot_id: Option<String>,
+}
+
+impl Journal {
+    /// Opens the journal and drops records older than the retention period
+    pub fn open(config: &JournalConfig) -> Self {
+        let path = if config.enabled { journal_path() } else { None };
+        if let Some(path) = &path {
+            if let Err(e) = prune(path, config.retention_days) {
+                warn!("Failed to apply journal retention: {}", e);
+            }
+        }
+        Journal {
+            path,
+            puuid: None,
+            riot_id: None,
+        }
+    }
+
+    /// The account stamped on every following record
+    pub fn set_account(&mut self, summoner: Option<&Summoner>) {
+        self.puuid = summoner.map(|summoner| summoner.puuid.clone());
+        self.riot_id = summoner.and_then(Summoner::riot_id);
+    }
+
+    pub fn record(&self, payload: &EventPayload) {
+        let (path, event) = match (&self.path, JournalEvent::from_game_event(payload.event)) {
+            (Some(path), Some(event)) => (path, event),
+            _ => return,
+        };
+
+        let record = JournalRecord {
+            at: payload.at,
+            event,
+            queue_id: payload.queue_id,
+            queue: payload.queue.clone(),
+            game_mode: payload.game_mode.clone(),
+            game_id: payload.game_id,
+            region: payload.region.clone(),
+            puuid: self.puuid.clone(),
+            riot_id: self.riot_id.clone(),
+            duration_secs: payload.duration_secs,
+        };
+        if let Err(e) = append(path, &record) {
+            warn!("Failed to write journal: {}", e);
+        }
+    }
+}
+
+/// `$XDG_DATA_HOME/league-reveal-console/journal.jsonl`
+pub fn journal_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(JOURNAL_DIR).join(JOURNAL_FILE))
+}
+
+fn append(path: &PathBuf, record: &JournalRecord) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+/// Records in file order. Lines that don't parse are skipped.
+fn read(path: &PathBuf) -> std::io::Result<Vec<JournalRecord>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(record) = serde_json::from_str(&line?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Rewrites the journal without records older than `retention_days` (0 keeps everything)
+fn prune(path: &PathBuf, retention_days: u32) -> std::io::Result<()> {
+    if retention_days == 0 || !path.exists() {
+        return Ok(());
+    }
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+    let records = read(path)?;
+    let kept: Vec<&JournalRecord> = records.iter().filter(|r| r.at >= cutoff).collect();
+    if kept.len() == records.len() {
+        return Ok(());
+    }
+
+    let mut contents = String::new();
+    for record in kept {
+        contents.push_str(&serde_json::to_string(record).unwrap_or_default());
+        contents.push('\n');
+    }
+    // Write next to the journal and rename, so a crash can't leave it half written
+    let tmp = path.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// `journal` subcommand
+pub fn query(args: &JournalArgs) -> Result<(), String> {
+    let path = journal_path().ok_or("No data directory for the journal")?;
+    let records = read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let cutoff = args
+        .days
+        .map(|days| Utc::now() - chrono::Duration::days(days));
+    let matching: Vec<&JournalRecord> = records
+        .iter()
+        .filter(|r| args.event.is_none_or(|event| r.event == event))
+        .filter(|r| cutoff.is_none_or(|cutoff| r.at >= cutoff))
+        .collect();
+    let shown = &matching[matching.len().saturating_sub(args.limit)..];
+
+    if args.json {
+        for record in shown {
+            println!("{}", serde_json::to_string(record).unwrap_or_default());
+        }
+        return Ok(());
+    }
+
+    if shown.is_empty() {
+        println!("No journal entries in {}", path.display());
+    }
+    for record in shown {
+        println!("{}", format_record(record));
+    }
+    Ok(())
+}
+
+fn format_record(record: &JournalRecord) -> String {
+    let event = serde_json::to_value(record.event)
+        .ok()
+        .and_then(|value| value.as_str().map(|s| s.to_string()))
+        .unwrap_or_default();
+    let mut line = format!(
+        "{}  {:<22}",
+        record.at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+        event
+    );
+    match (&record.queue, record.queue_id) {
+        (Some(queue), Some(id)) => line.push_str(&format!("  {} ({})", queue, id)),
+        (None, Some(id)) => line.push_str(&format!("  queue {}", id)),
+        _ => {}
+    }
+    if let Some(region) = &record.region {
+        line.push_str(&format!("  {}", region));
+    }
+    if let Some(duration) = record.duration_secs {
+        line.push_str(&format!("  {}m{:02}s", duration / 60, duration % 60));
+    }
+    line.trim_end().to_string()
+}
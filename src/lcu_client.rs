@@ -1,6 +1,10 @@
-use reqwest::Client;
+use crate::metrics;
+use reqwest::{Client, Method, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Instant;
 use sysinfo::{ProcessExt, System, SystemExt};
+use tracing::debug;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LCUClientInfo {
@@ -9,40 +13,137 @@ pub struct LCUClientInfo {
     pub protocol: String,
 }
 
+#[derive(Debug)]
+pub enum LcuError {
+    /// The client isn't reachable, usually because it is closing or restarting
+    Connect(reqwest::Error),
+    Timeout(reqwest::Error),
+    /// The endpoint answered with a non-success status. `body` is the
+    /// client's error response, usually JSON with an `errorCode` and `message`.
+    Status {
+        endpoint: String,
+        status: StatusCode,
+        body: String,
+    },
+    Request(reqwest::Error),
+}
+
+impl LcuError {
+    /// Short label for logs and metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LcuError::Connect(_) => "connect",
+            LcuError::Timeout(_) => "timeout",
+            LcuError::Status { .. } => "status",
+            LcuError::Request(_) => "request",
+        }
+    }
+
+    /// The endpoint doesn't exist right now, e.g. the champ-select session
+    /// outside champ select; polling expects this
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, LcuError::Status { status, .. } if *status == StatusCode::NOT_FOUND)
+    }
+}
+
+impl From<reqwest::Error> for LcuError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_connect() {
+            LcuError::Connect(e)
+        } else if e.is_timeout() {
+            LcuError::Timeout(e)
+        } else {
+            LcuError::Request(e)
+        }
+    }
+}
+
+impl fmt::Display for LcuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LcuError::Connect(e) => write!(f, "League Client unreachable: {}", e),
+            LcuError::Timeout(e) => write!(f, "League Client timed out: {}", e),
+            LcuError::Status {
+                endpoint, status, ..
+            } => write!(f, "{} returned {}", endpoint, status),
+            LcuError::Request(e) => write!(f, "League Client request failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LcuError {}
+
 pub struct LCUClient {
     client: Client,
     base_url: String,
+    password: String,
 }
 
 impl LCUClient {
-    pub async fn new(info: &LCUClientInfo, use_remoting: bool) -> Result<Self, Box<dyn std::error::Error>> {
+    /// The app and remoting APIs are served from the same port, so one kind of
+    /// client serves both
+    pub async fn new(info: &LCUClientInfo) -> Result<Self, Box<dyn std::error::Error>> {
         let client = Client::builder()
             .danger_accept_invalid_certs(true)
             .build()?;
 
-        let base_url = if use_remoting {
-            format!("{}://127.0.0.1:{}/", info.protocol, info.port)
-        } else {
-            format!("{}://127.0.0.1:{}/", info.protocol, info.port)
-        };
+        let base_url = format!("{}://127.0.0.1:{}/", info.protocol, info.port);
 
-        Ok(LCUClient { client, base_url })
+        Ok(LCUClient {
+            client,
+            base_url,
+            password: info.password.clone(),
+        })
     }
 
-    pub async fn get(&self, endpoint: &str) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+    pub async fn get(&self, endpoint: &str) -> Result<reqwest::Response, LcuError> {
         let url = format!("{}{}", self.base_url, endpoint.trim_start_matches('/'));
-        let response = self.client.get(&url).send().await?;
-        Ok(response)
+        self.send(endpoint, self.client.get(&url)).await
     }
 
-    pub async fn post(&self, endpoint: &str, body: serde_json::Value) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+    pub async fn post(&self, endpoint: &str, body: serde_json::Value) -> Result<reqwest::Response, LcuError> {
         let url = format!("{}{}", self.base_url, endpoint.trim_start_matches('/'));
-        let response = self.client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await?;
-        Ok(response)
+        self.send(endpoint, self.client.post(&url).json(&body)).await
+    }
+
+    /// Any method, for one-off calls like the `raw` subcommand
+    pub async fn request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<reqwest::Response, LcuError> {
+        let url = format!("{}{}", self.base_url, endpoint.trim_start_matches('/'));
+        let mut request = self.client.request(method, &url);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        self.send(endpoint, request).await
+    }
+
+    async fn send(
+        &self,
+        endpoint: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, LcuError> {
+        let started = Instant::now();
+        let result = match request.basic_auth("riot", Some(&self.password)).send().await {
+            Ok(response) if response.status().is_success() => Ok(response),
+            Ok(response) => Err(LcuError::Status {
+                endpoint: endpoint.to_string(),
+                status: response.status(),
+                body: response.text().await.unwrap_or_default(),
+            }),
+            Err(e) => Err(LcuError::from(e)),
+        };
+
+        let elapsed = started.elapsed();
+        match &result {
+            Ok(response) => debug!("{} -> {} in {:?}", endpoint, response.status(), elapsed),
+            Err(e) => debug!("{} failed in {:?}: {}", endpoint, elapsed, e),
+        }
+        metrics::observe_lcu_request(endpoint, elapsed, result.as_ref().err());
+        result
     }
 }
 
@@ -50,7 +151,7 @@ pub async fn find_league_client() -> Option<LCUClientInfo> {
     let mut system = System::new_all();
     system.refresh_processes();
 
-    for (_, process) in system.processes() {
+    for process in system.processes().values() {
         if process.name().contains("LeagueClient") {
             let cmd_line = process.cmd();
             if let Some(info) = parse_league_args(cmd_line) {
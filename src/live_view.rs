@@ -0,0 +1,352 @@
+use crate::champ_select::{Action, ChampSelectSession, Team, Type};
+use crate::game_data::GameDataCache;
+use crate::lobby::position_label;
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+
+/// Console view of the champ-select board, redrawn in place on every update
+#[derive(Default)]
+pub struct LiveView {
+    lines_drawn: usize,
+    last_frame: Vec<String>,
+}
+
+impl LiveView {
+    /// Forgets the previous frame so the next one is drawn below anything
+    /// printed since, instead of overwriting it
+    pub fn reset(&mut self) {
+        self.lines_drawn = 0;
+        self.last_frame.clear();
+    }
+
+    pub fn render(&mut self, session: &ChampSelectSession, game_data: Option<&GameDataCache>) {
+        let is_terminal = std::io::stdout().is_terminal();
+        // Without a terminal we can't redraw, so leave out the ticking timer and
+        // only print when the board itself changes
+        let frame = build_frame(session, game_data, is_terminal);
+        if frame == self.last_frame {
+            return;
+        }
+
+        let mut out = std::io::stdout().lock();
+        if is_terminal && self.lines_drawn > 0 {
+            // Move to the start of the previous frame and clear everything below
+            let _ = write!(out, "\x1b[{}F\x1b[J", self.lines_drawn);
+        }
+        for line in &frame {
+            let _ = writeln!(out, "{}", line);
+        }
+        let _ = out.flush();
+
+        self.lines_drawn = frame.len();
+        self.last_frame = frame;
+    }
+}
+
+/// The board as plain lines, for UIs that draw it themselves
+pub fn board(session: &ChampSelectSession, game_data: Option<&GameDataCache>) -> Vec<String> {
+    build_frame(session, game_data, true)
+}
+
+/// Machine-readable champ-select state. Carries champions, positions and the
+/// timer but never player names or ids.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChampSelectSnapshot {
+    pub phase: String,
+    /// `None` when the phase has no time limit
+    pub time_left_ms: Option<u64>,
+    pub my_team: Vec<CellSnapshot>,
+    pub their_team: Vec<CellSnapshot>,
+    pub my_team_bans: Vec<ChampionSnapshot>,
+    pub their_team_bans: Vec<ChampionSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CellSnapshot {
+    pub cell_id: i64,
+    pub position: Option<&'static str>,
+    pub champion: Option<ChampionSnapshot>,
+    /// The champion is locked in rather than hovered
+    pub locked: bool,
+    /// The cell has a pick or ban in progress
+    pub acting: bool,
+    pub is_local_player: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChampionSnapshot {
+    pub id: i64,
+    pub name: Option<String>,
+}
+
+enum PickState {
+    Locked(i64),
+    Hovering(i64),
+    Empty,
+}
+
+/// The board as structured data, for the local API
+pub fn snapshot(session: &ChampSelectSession, game_data: Option<&GameDataCache>) -> ChampSelectSnapshot {
+    let actions: Vec<&Action> = session.actions.iter().flatten().collect();
+    let champion = |id: i64| ChampionSnapshot {
+        id,
+        name: game_data
+            .and_then(|data| data.champion_name(id))
+            .map(|name| name.to_string()),
+    };
+    let cell = |member: &Team, ally: bool| {
+        let cell_actions = actions_for(member, &actions);
+        let pick = if ally {
+            pick_state(member, &cell_actions)
+        } else {
+            // Only what the client already reveals, never intents
+            match member.champion_id {
+                0 => PickState::Empty,
+                id => PickState::Locked(id),
+            }
+        };
+        let (champion, locked) = match pick {
+            PickState::Locked(id) => (Some(champion(id)), true),
+            PickState::Hovering(id) => (Some(champion(id)), false),
+            PickState::Empty => (None, false),
+        };
+        CellSnapshot {
+            cell_id: member.cell_id,
+            position: position_label(&member.assigned_position.to_lowercase()),
+            champion,
+            locked,
+            acting: is_acting(&cell_actions),
+            is_local_player: ally && member.cell_id == session.local_player_cell_id,
+        }
+    };
+    let bans = |ally: bool| {
+        actions
+            .iter()
+            .filter(|action| matches!(action.action_type, Type::Ban))
+            .filter(|action| action.is_ally_action == ally && action.completed)
+            .filter(|action| action.champion_id != 0)
+            .map(|action| champion(action.champion_id))
+            .collect()
+    };
+
+    ChampSelectSnapshot {
+        phase: session.timer.phase.clone(),
+        time_left_ms: (!session.timer.is_infinite)
+            .then_some(session.timer.adjusted_time_left_in_phase),
+        my_team: session.my_team.iter().map(|member| cell(member, true)).collect(),
+        their_team: session.their_team.iter().map(|member| cell(member, false)).collect(),
+        my_team_bans: bans(true),
+        their_team_bans: bans(false),
+    }
+}
+
+/// One line summary of a cell's champion and spells, e.g. `Ahri (hovering) - Flash / Ignite`
+pub fn describe_cell(member: &Team, game_data: &GameDataCache) -> String {
+    let champion = if member.champion_id != 0 {
+        game_data.champion_name(member.champion_id).map(|name| name.to_string())
+    } else {
+        game_data
+            .champion_name(member.champion_pick_intent)
+            .map(|name| format!("{} (hovering)", name))
+    };
+    let spells = [member.spell1_id, member.spell2_id]
+        .iter()
+        .map(|id| game_data.spell_name(*id).unwrap_or("?"))
+        .collect::<Vec<_>>()
+        .join(" / ");
+
+    format!(
+        "{} - {}",
+        champion.unwrap_or_else(|| "no champion".to_string()),
+        spells
+    )
+}
+
+fn build_frame(
+    session: &ChampSelectSession,
+    game_data: Option<&GameDataCache>,
+    show_timer: bool,
+) -> Vec<String> {
+    let actions: Vec<&Action> = session.actions.iter().flatten().collect();
+
+    let mut header = format!("Champ Select - {}", phase_label(&session.timer.phase));
+    if show_timer {
+        header.push_str(&format!(" - {}", time_left(session)));
+    }
+    if let Some(turn) = turn_label(session, &actions) {
+        header.push_str(&format!(" - {}", turn));
+    }
+
+    let mut lines = vec![header, "Your team:".to_string()];
+    for member in &session.my_team {
+        lines.push(format_cell(session, member, &actions, game_data, true));
+    }
+    if !session.their_team.is_empty() {
+        lines.push("Enemy team:".to_string());
+        for member in &session.their_team {
+            lines.push(format_cell(session, member, &actions, game_data, false));
+        }
+    }
+    lines
+}
+
+fn format_cell(
+    session: &ChampSelectSession,
+    member: &Team,
+    actions: &[&Action],
+    game_data: Option<&GameDataCache>,
+    ally: bool,
+) -> String {
+    let cell_actions = actions_for(member, actions);
+    let acting = is_acting(&cell_actions);
+
+    let position = position_label(&member.assigned_position.to_lowercase()).unwrap_or("-");
+    let mut line = format!(
+        "  {} {:<8}{}",
+        if acting { ">" } else { " " },
+        position,
+        champion_state(member, &cell_actions, game_data, ally)
+    );
+
+    // Enemy spells are hidden by the client until loading screen
+    if ally && member.spell1_id != 0 && member.spell2_id != 0 {
+        line.push_str(&format!(
+            "  [{} / {}]",
+            spell_name(member.spell1_id, game_data),
+            spell_name(member.spell2_id, game_data)
+        ));
+    }
+
+    if let Some(ban) = cell_actions
+        .iter()
+        .find(|action| matches!(action.action_type, Type::Ban))
+    {
+        if ban.completed && ban.champion_id != 0 {
+            line.push_str(&format!(
+                "  banned {}",
+                champion_name(ban.champion_id, game_data)
+            ));
+        } else if ban.is_in_progress {
+            line.push_str("  banning");
+        }
+    }
+
+    if ally && member.cell_id == session.local_player_cell_id {
+        line.push_str("  (you)");
+    }
+    line
+}
+
+fn champion_state(
+    member: &Team,
+    cell_actions: &[&Action],
+    game_data: Option<&GameDataCache>,
+    ally: bool,
+) -> String {
+    // Only show enemy champions the client already reveals, never intents
+    if !ally {
+        return match member.champion_id {
+            0 => "?".to_string(),
+            id => champion_name(id, game_data),
+        };
+    }
+
+    match pick_state(member, cell_actions) {
+        PickState::Locked(id) => format!("{} (locked)", champion_name(id, game_data)),
+        PickState::Hovering(id) => format!("{} (hovering)", champion_name(id, game_data)),
+        PickState::Empty => "-".to_string(),
+    }
+}
+
+fn actions_for<'a>(member: &Team, actions: &[&'a Action]) -> Vec<&'a Action> {
+    actions
+        .iter()
+        .filter(|action| action.actor_cell_id == member.cell_id)
+        .copied()
+        .collect()
+}
+
+fn is_acting(cell_actions: &[&Action]) -> bool {
+    cell_actions
+        .iter()
+        .any(|action| action.is_in_progress && !action.completed)
+}
+
+fn pick_state(member: &Team, cell_actions: &[&Action]) -> PickState {
+    let pick = cell_actions
+        .iter()
+        .find(|action| matches!(action.action_type, Type::Pick));
+    let locked = match pick {
+        Some(pick) => pick.completed,
+        // Modes without pick actions (e.g. ARAM) assign champions directly
+        None => member.champion_id != 0,
+    };
+
+    if locked && member.champion_id != 0 {
+        return PickState::Locked(member.champion_id);
+    }
+
+    let hover = pick
+        .map(|pick| pick.champion_id)
+        .filter(|id| *id != 0)
+        .unwrap_or(member.champion_pick_intent);
+    match hover {
+        0 => PickState::Empty,
+        id => PickState::Hovering(id),
+    }
+}
+
+fn turn_label(session: &ChampSelectSession, actions: &[&Action]) -> Option<String> {
+    let current: Vec<&&Action> = actions
+        .iter()
+        .filter(|action| action.is_in_progress && !action.completed)
+        .collect();
+    let first = current.first()?;
+    let (verb, acting) = match first.action_type {
+        Type::Ban => ("ban", "banning"),
+        Type::Pick => ("pick", "picking"),
+        Type::TenBansReveal => return None,
+    };
+
+    if current
+        .iter()
+        .any(|action| action.actor_cell_id == session.local_player_cell_id)
+    {
+        Some(format!("Your turn to {}", verb))
+    } else if first.is_ally_action {
+        Some(format!("Ally {}", acting))
+    } else {
+        Some(format!("Enemy {}", acting))
+    }
+}
+
+fn phase_label(phase: &str) -> &str {
+    match phase {
+        "PLANNING" => "Planning",
+        "BAN_PICK" => "Ban/Pick",
+        "FINALIZATION" => "Finalization",
+        "GAME_STARTING" => "Game starting",
+        _ => phase,
+    }
+}
+
+fn time_left(session: &ChampSelectSession) -> String {
+    if session.timer.is_infinite {
+        return "no time limit".to_string();
+    }
+    format!("{}s left", session.timer.adjusted_time_left_in_phase.div_ceil(1000))
+}
+
+fn champion_name(id: i64, game_data: Option<&GameDataCache>) -> String {
+    game_data
+        .and_then(|data| data.champion_name(id))
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("Champion {}", id))
+}
+
+fn spell_name(id: i64, game_data: Option<&GameDataCache>) -> String {
+    game_data
+        .and_then(|data| data.spell_name(id))
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("Spell {}", id))
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use crate::champ_select::ChampSelectSession;
 use crate::lcu_client::LCUClient;
+use crate::logging;
+use crate::summoner;
+use tracing::{debug, warn};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
     pub cid: String,
     pub game_name: String,
@@ -11,6 +15,10 @@ pub struct Participant {
     pub pid: String,
     pub puuid: String,
     pub region: String,
+    #[serde(default)]
+    pub assigned_position: String,
+    #[serde(default)]
+    pub cell_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,24 +26,371 @@ pub struct Lobby {
     pub participants: Vec<Participant>,
 }
 
-pub async fn get_lobby_info(app_client: &LCUClient) -> Lobby {
-    let response = app_client
-        .get("/chat/v5/participants")
-        .await
-        .unwrap();
-    
-    let team: Lobby = response.json().await.unwrap();
+pub async fn get_lobby_info(
+    app_client: &LCUClient,
+    session: Option<&ChampSelectSession>,
+    exclude_self: bool,
+) -> Lobby {
+    let team: Lobby = match app_client.get("/chat/v5/participants").await {
+        Ok(response) => match response.json().await {
+            Ok(team) => team,
+            Err(e) => {
+                warn!("Failed to parse chat participants: {:?}", e);
+                return Lobby { participants: Vec::new() };
+            }
+        },
+        Err(e) => {
+            warn!("Failed to get chat participants: {:?}", e);
+            return Lobby { participants: Vec::new() };
+        }
+    };
+
+    // Prefer the current champ-select room so members of a dodged lobby don't leak in
+    let mut chat_room = session
+        .map(|s| s.chat_details.multi_user_chat_id.as_str())
+        .filter(|id| !id.is_empty());
+    // If the cid format ever differs, an exact match would silently leave nobody
+    if let Some(room) = chat_room {
+        if !team.participants.iter().any(|p| in_room(p, Some(room))) {
+            debug!("No chat participant in room {}, matching any champ-select room", room);
+            chat_room = None;
+        }
+    }
+    let local_puuid = session.and_then(local_player_puuid);
 
-    // filter out all cids that contain champ-select
-    let team_participants = team
-        .participants
-        .into_iter()
-        .filter(|p| p.cid.contains("champ-select"))
-        .collect::<Vec<Participant>>();
+    let mut team_participants: Vec<Participant> = Vec::new();
+    for participant in team.participants {
+        if !in_room(&participant, chat_room) {
+            continue;
+        }
+
+        if exclude_self && local_puuid == Some(participant.puuid.as_str()) {
+            continue;
+        }
+
+        // Repeated chat joins show up as duplicate entries for the same player
+        if team_participants
+            .iter()
+            .any(|p| !p.puuid.is_empty() && p.puuid == participant.puuid)
+        {
+            continue;
+        }
+
+        if participant.game_name.is_empty() || participant.game_tag.is_empty() {
+            warn!(
+                "Skipping participant {} with missing Riot ID",
+                logging::puuid(&participant.puuid)
+            );
+            continue;
+        }
+
+        team_participants.push(participant);
+    }
 
-    let team = Lobby {
+    Lobby {
         participants: team_participants,
+    }
+}
+
+fn in_room(participant: &Participant, room: Option<&str>) -> bool {
+    match room {
+        Some(room) => participant.cid.split('@').next() == Some(room),
+        None => participant.cid.contains("champ-select"),
+    }
+}
+
+fn local_player_puuid(session: &ChampSelectSession) -> Option<&str> {
+    session
+        .my_team
+        .iter()
+        .find(|member| member.cell_id == session.local_player_cell_id)
+        .map(|member| member.puuid.as_str())
+        .filter(|puuid| !puuid.is_empty())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyMember {
+    pub is_leader: bool,
+    pub puuid: String,
+    pub summoner_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartyLobby {
+    pub local_member: LobbyMember,
+    pub members: Vec<LobbyMember>,
+    pub party_id: String,
+}
+
+/// Fetches our premade lobby. Returns `None` when we are not in a lobby.
+pub async fn get_party_lobby(remoting_client: &LCUClient) -> Option<PartyLobby> {
+    let response = remoting_client.get("/lol-lobby/v2/lobby").await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    match response.json().await {
+        Ok(party) => Some(party),
+        Err(e) => {
+            warn!("Failed to parse lobby: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Lists the premade lobby members in the same shape as champ-select participants
+pub async fn get_party_participants(
+    remoting_client: &LCUClient,
+    party: &PartyLobby,
+    exclude_self: bool,
+) -> Lobby {
+    let mut participants = Vec::new();
+    for member in &party.members {
+        if exclude_self && member.puuid == party.local_member.puuid {
+            continue;
+        }
+        if let Some(participant) =
+            participant_from_puuid(remoting_client, &member.puuid).await
+        {
+            participants.push(participant);
+        }
+    }
+
+    Lobby { participants }
+}
+
+/// Lists the players of an in-progress game (e.g. a TFT lobby) by puuid
+pub async fn get_game_participants(
+    remoting_client: &LCUClient,
+    puuids: &[String],
+    exclude_puuid: Option<&str>,
+) -> Lobby {
+    let mut participants = Vec::new();
+    for puuid in puuids {
+        if exclude_puuid == Some(puuid.as_str()) {
+            continue;
+        }
+        if let Some(participant) = participant_from_puuid(remoting_client, puuid).await {
+            participants.push(participant);
+        }
+    }
+
+    Lobby { participants }
+}
+
+async fn participant_from_puuid(
+    remoting_client: &LCUClient,
+    puuid: &str,
+) -> Option<Participant> {
+    // Lobby and game members only carry the puuid, so resolve the Riot ID separately
+    let (game_name, game_tag) = match summoner::get_riot_id(remoting_client, puuid).await {
+        Some(riot_id) => riot_id,
+        None => {
+            warn!("Skipping player with missing Riot ID");
+            return None;
+        }
     };
 
-    team
-}
\ No newline at end of file
+    Some(Participant {
+        cid: String::new(),
+        // Chat lists players by game name, so do the same
+        name: game_name.clone(),
+        game_name,
+        game_tag,
+        muted: false,
+        pid: String::new(),
+        puuid: puuid.to_string(),
+        region: String::new(),
+        assigned_position: String::new(),
+        cell_id: None,
+    })
+}
+
+/// Lists our team top/jungle/mid/bottom/support using the champ-select session.
+/// Players without an assigned position (e.g. blind pick) keep pick order.
+pub fn order_by_position(lobby: &mut Lobby, session: &ChampSelectSession) {
+    for participant in lobby.participants.iter_mut() {
+        if let Some(member) = session
+            .my_team
+            .iter()
+            .find(|member| !member.puuid.is_empty() && member.puuid == participant.puuid)
+        {
+            participant.assigned_position = member.assigned_position.to_lowercase();
+            participant.cell_id = Some(member.cell_id);
+        }
+    }
+
+    lobby.participants.sort_by_key(|p| {
+        (
+            position_order(&p.assigned_position),
+            p.cell_id.unwrap_or(i64::MAX),
+        )
+    });
+}
+
+/// Splits an Arena champ select into our duo and everyone else
+pub fn split_arena_duo(lobby: &Lobby, session: &ChampSelectSession) -> (Lobby, Lobby) {
+    let local_team = session
+        .my_team
+        .iter()
+        .find(|member| member.cell_id == session.local_player_cell_id)
+        .map(|member| member.team);
+    let duo_puuids: Vec<&str> = session
+        .my_team
+        .iter()
+        .filter(|member| Some(member.team) == local_team && !member.puuid.is_empty())
+        .map(|member| member.puuid.as_str())
+        .collect();
+
+    let (duo, others): (Vec<Participant>, Vec<Participant>) = lobby
+        .participants
+        .iter()
+        .cloned()
+        .partition(|p| duo_puuids.contains(&p.puuid.as_str()));
+
+    (Lobby { participants: duo }, Lobby { participants: others })
+}
+
+fn position_order(position: &str) -> usize {
+    match position {
+        "top" => 0,
+        "jungle" => 1,
+        "middle" => 2,
+        "bottom" => 3,
+        "utility" => 4,
+        _ => 5,
+    }
+}
+
+pub fn position_label(position: &str) -> Option<&'static str> {
+    match position {
+        "top" => Some("Top"),
+        "jungle" => Some("Jungle"),
+        "middle" => Some("Mid"),
+        "bottom" => Some("Bot"),
+        "utility" => Some("Support"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn participant(puuid: &str) -> Participant {
+        Participant {
+            cid: String::new(),
+            game_name: puuid.to_string(),
+            game_tag: "EUW".to_string(),
+            muted: false,
+            name: puuid.to_string(),
+            pid: String::new(),
+            puuid: puuid.to_string(),
+            region: String::new(),
+            assigned_position: String::new(),
+            cell_id: None,
+        }
+    }
+
+    /// Only the fields the app reads; the rest of the session defaults
+    fn session(team: &[(&str, &str, i64)]) -> ChampSelectSession {
+        let my_team: Vec<_> = team
+            .iter()
+            .map(|(puuid, position, cell_id)| {
+                json!({
+                    "assignedPosition": position,
+                    "cellId": cell_id,
+                    "championId": 0,
+                    "championPickIntent": 0,
+                    "puuid": puuid,
+                    "spell1Id": 0,
+                    "spell2Id": 0,
+                    "team": 1,
+                })
+            })
+            .collect();
+        serde_json::from_value(json!({
+            "actions": [],
+            "localPlayerCellId": 0,
+            "myTeam": my_team,
+            "theirTeam": [],
+            "timer": { "adjustedTimeLeftInPhase": 0, "isInfinite": false, "phase": "PLANNING" },
+        }))
+        .unwrap()
+    }
+
+    fn order(lobby: &Lobby) -> Vec<&str> {
+        lobby.participants.iter().map(|p| p.puuid.as_str()).collect()
+    }
+
+    #[test]
+    fn orders_top_to_support() {
+        let mut lobby = Lobby {
+            participants: ["sup", "mid", "top", "bot", "jgl"].map(participant).to_vec(),
+        };
+        let session = session(&[
+            ("top", "TOP", 3),
+            ("jgl", "JUNGLE", 1),
+            ("mid", "MIDDLE", 0),
+            ("bot", "BOTTOM", 4),
+            ("sup", "UTILITY", 2),
+        ]);
+
+        order_by_position(&mut lobby, &session);
+
+        assert_eq!(order(&lobby), ["top", "jgl", "mid", "bot", "sup"]);
+        assert_eq!(lobby.participants[0].assigned_position, "top");
+        assert_eq!(lobby.participants[0].cell_id, Some(3));
+    }
+
+    #[test]
+    fn without_positions_keeps_pick_order() {
+        let mut lobby = Lobby {
+            participants: ["c", "a", "b"].map(participant).to_vec(),
+        };
+        let session = session(&[("a", "", 0), ("b", "", 1), ("c", "", 2)]);
+
+        order_by_position(&mut lobby, &session);
+
+        assert_eq!(order(&lobby), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn players_missing_from_the_session_go_last() {
+        let mut lobby = Lobby {
+            participants: ["unknown", "mid"].map(participant).to_vec(),
+        };
+        let session = session(&[("mid", "MIDDLE", 0)]);
+
+        order_by_position(&mut lobby, &session);
+
+        assert_eq!(order(&lobby), ["mid", "unknown"]);
+        assert_eq!(lobby.participants[1].cell_id, None);
+    }
+
+    #[test]
+    fn chat_room_matching() {
+        let mut player = participant("a");
+        player.cid = "c1~abc123@champ-select.eu1.pvp.net".to_string();
+
+        assert!(in_room(&player, Some("c1~abc123")));
+        assert!(!in_room(&player, Some("c1~other")));
+        assert!(in_room(&player, None));
+
+        player.cid = "lobby-abc@lobby.eu1.pvp.net".to_string();
+        assert!(!in_room(&player, None));
+    }
+
+    #[test]
+    fn position_labels() {
+        assert_eq!(position_label("top"), Some("Top"));
+        assert_eq!(position_label("jungle"), Some("Jungle"));
+        assert_eq!(position_label("middle"), Some("Mid"));
+        assert_eq!(position_label("bottom"), Some("Bot"));
+        assert_eq!(position_label("utility"), Some("Support"));
+        assert_eq!(position_label(""), None);
+        assert_eq!(position_label("TOP"), None);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::config::LoggingConfig;
+use crate::console::ConsoleWriter;
+use std::cell::Cell;
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+const STATE_DIR: &str = "league-reveal-console";
+const LOG_FILE_PREFIX: &str = "league-reveal-console";
+
+static SHOW_SENSITIVE: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // Set while an event is formatted for a sink that must not see sensitive values
+    static REDACT: Cell<bool> = const { Cell::new(false) };
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Sets up console (and optionally file) logging. The returned guard flushes
+/// the log file when dropped, so keep it alive until exit.
+pub fn init(config: &LoggingConfig) -> Result<Option<WorkerGuard>, String> {
+    // RUST_LOG wins over the config file, like in most Rust tools
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::try_new(&config.level)
+            .map_err(|e| format!("Invalid [logging] level {:?}: {}", config.level, e))?,
+    };
+    SHOW_SENSITIVE.store(config.show_sensitive, Ordering::Relaxed);
+
+    let mut layers: Vec<BoxedLayer> = vec![format_layer(config.json, true, ConsoleWriter)];
+    let mut guard = None;
+    if config.file {
+        let dir = log_dir().ok_or("No state directory for log files")?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let appender = RollingFileAppender::builder()
+            .rotation(rotation(&config.rotation)?)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(config.max_files)
+            .build(&dir)
+            .map_err(|e| format!("Failed to open log file in {}: {}", dir.display(), e))?;
+        let (writer, file_guard) = tracing_appender::non_blocking(appender);
+        layers.push(format_layer(config.json, false, writer));
+        guard = Some(file_guard);
+    }
+
+    tracing_subscriber::registry()
+        .with(layers.with_filter(filter))
+        .try_init()
+        .map_err(|e| format!("Failed to set up logging: {}", e))?;
+    Ok(guard)
+}
+
+fn format_layer<W>(json: bool, console: bool, writer: W) -> BoxedLayer
+where
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    // Info and up on the console is what the user reads, so it stays legible;
+    // anything that is kept or parsed gets the redacted values
+    let always = json || !console;
+    if json {
+        layer
+            .json()
+            .map_event_format(|format| Redacting { format, always })
+            .boxed()
+    } else if console {
+        // Module paths are noise for someone watching the console
+        layer
+            .with_target(false)
+            .with_ansi(std::io::stdout().is_terminal())
+            .map_event_format(|format| Redacting { format, always })
+            .boxed()
+    } else {
+        layer
+            .with_ansi(false)
+            .map_event_format(|format| Redacting { format, always })
+            .boxed()
+    }
+}
+
+/// Redacts `Sensitive` values in debug and trace events, or in every event
+/// with `always`
+struct Redacting<F> {
+    format: F,
+    always: bool,
+}
+
+impl<S, N, F> FormatEvent<S, N> for Redacting<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let redact = self.always || *event.metadata().level() > Level::INFO;
+        let previous = REDACT.replace(redact);
+        let result = self.format.format_event(ctx, writer, event);
+        REDACT.set(previous);
+        result
+    }
+}
+
+fn rotation(rotation: &str) -> Result<Rotation, String> {
+    match rotation {
+        "hourly" => Ok(Rotation::HOURLY),
+        "daily" => Ok(Rotation::DAILY),
+        "never" => Ok(Rotation::NEVER),
+        _ => Err(format!(
+            "Invalid [logging] rotation {:?}, expected hourly, daily or never",
+            rotation
+        )),
+    }
+}
+
+/// `$XDG_STATE_HOME/league-reveal-console`, or the local data dir where there is no state dir
+pub fn log_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(STATE_DIR))
+}
+
+fn show_sensitive() -> bool {
+    SHOW_SENSITIVE.load(Ordering::Relaxed)
+}
+
+/// A value that is shown in full on the console and redacted in debug and
+/// trace logs, log files and JSON logs, unless sensitive logging is enabled.
+/// Outside of logging (e.g. `println!`) it is always shown in full.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sensitive {
+    clear: String,
+    redacted: String,
+}
+
+impl Sensitive {
+    fn new(clear: String, redacted: String) -> Self {
+        Sensitive { clear, redacted }
+    }
+
+    /// Appends text that isn't sensitive itself
+    pub fn push_str(&mut self, text: &str) {
+        self.clear.push_str(text);
+        self.redacted.push_str(text);
+    }
+
+    pub fn join(values: impl IntoIterator<Item = Sensitive>, separator: &str) -> Self {
+        let mut joined = Sensitive::default();
+        for (i, value) in values.into_iter().enumerate() {
+            if i > 0 {
+                joined.push_str(separator);
+            }
+            joined.clear.push_str(&value.clear);
+            joined.redacted.push_str(&value.redacted);
+        }
+        joined
+    }
+}
+
+impl fmt::Display for Sensitive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if REDACT.get() && !show_sensitive() {
+            f.write_str(&self.redacted)
+        } else {
+            f.write_str(&self.clear)
+        }
+    }
+}
+
+/// Riot ID for logs, redacted to e.g. `F***#EUW`
+pub fn riot_id(game_name: &str, tag_line: &str) -> Sensitive {
+    let first: String = game_name.chars().take(1).collect();
+    Sensitive::new(
+        format!("{}#{}", game_name, tag_line),
+        format!("{}***#{}", first, tag_line),
+    )
+}
+
+/// puuid for logs, redacted to its first group, e.g. `8f3c2a1b…`
+pub fn puuid(puuid: &str) -> Sensitive {
+    let first = puuid.split('-').next().unwrap_or_default();
+    Sensitive::new(puuid.to_string(), format!("{}…", first))
+}
+
+/// Tokens and passwords for logs
+pub fn secret(value: &str) -> String {
+    if show_sensitive() {
+        value.to_string()
+    } else {
+        "***".to_string()
+    }
+}
+
+/// Links carry Riot IDs in their path or query, so redacting keeps only the site
+pub fn url(url: &str) -> Sensitive {
+    let host_end = url
+        .find("://")
+        .map(|scheme| scheme + 3)
+        .and_then(|start| url[start..].find('/').map(|end| start + end))
+        .unwrap_or(url.len());
+    Sensitive::new(url.to_string(), format!("{}/…", &url[..host_end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// What one sink writes for an info and a debug line about the same player
+    fn logged(json: bool, console: bool) -> String {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(format_layer(json, console, buffer.clone()).with_filter(EnvFilter::new("debug")));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("Team: {}", riot_id("Faker", "KR1"));
+            tracing::debug!(
+                "Opening {}",
+                url("https://www.op.gg/multisearch/kr?summoners=Faker%23KR1")
+            );
+        });
+        let text = buffer.0.lock().unwrap().clone();
+        String::from_utf8(text).unwrap()
+    }
+
+    #[test]
+    fn console_shows_info_in_clear_text() {
+        let console = logged(false, true);
+        assert!(console.contains("Team: Faker#KR1"), "{}", console);
+        assert!(
+            console.contains("Opening https://www.op.gg/…"),
+            "{}",
+            console
+        );
+        assert!(!console.contains("summoners="), "{}", console);
+    }
+
+    #[test]
+    fn files_and_json_are_redacted() {
+        for (json, console) in [(false, false), (true, false), (true, true)] {
+            let logged = logged(json, console);
+            assert!(logged.contains("F***#KR1"), "{}", logged);
+            assert!(!logged.contains("Faker"), "{}", logged);
+        }
+    }
+
+    #[test]
+    fn outside_of_logging_values_are_shown_in_full() {
+        let team = Sensitive::join([riot_id("Faker", "KR1"), riot_id("Keria", "KR2")], ", ");
+        assert_eq!(team.to_string(), "Faker#KR1, Keria#KR2");
+        assert_eq!(url("https://u.gg/x").to_string(), "https://u.gg/x");
+        assert_eq!(
+            puuid("8f3c2a1b-0000-4000-8000-000000000001").redacted,
+            "8f3c2a1b…"
+        );
+    }
+}
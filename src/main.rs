@@ -1,112 +1,412 @@
-mod analytics;
+mod api;
+mod browser;
 mod champ_select;
+mod cli;
+mod codegen;
+mod commands;
+mod config;
+mod console;
+mod discord;
+mod drift;
+mod events;
+mod game_data;
+mod gameflow;
+mod journal;
+mod live_view;
 mod lobby;
+mod logging;
+mod metrics;
+#[cfg(feature = "notifications")]
+mod notifications;
+mod overlay;
 mod region;
+mod repl;
+mod service;
+mod sound;
+mod state;
 mod summoner;
+#[cfg(feature = "tui")]
+mod tui;
 mod utils;
+mod webhooks;
 mod lcu_client;
 
-use crate::region::RegionInfo;
-use crate::utils::display_champ_select;
-use crate::lcu_client::{LCUClient, LCUClientInfo};
-use std::time::Duration;
-use tokio::sync::Mutex;
+use crate::browser::Browser;
+use crate::cli::{Cli, Command};
+use crate::config::Config;
+use crate::discord::DiscordPresence;
+use crate::events::{EventPayload, GameEvent};
+use crate::game_data::GameDataCache;
+use crate::gameflow::GameflowSession;
+use crate::utils::{display_arena_champ_select, display_champ_select, display_tft_links};
+use crate::champ_select::ChampSelectSession;
+use crate::lcu_client::LCUClient;
+use crate::journal::Journal;
+use crate::live_view::LiveView;
+use crate::lobby::Lobby;
+use crate::sound::Sound;
+use crate::state::SharedState;
+use crate::webhooks::Webhooks;
+use clap::Parser;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 // Hardcoded configuration as requested
 const AUTO_OPEN_MULTI: bool = true;
 const AUTO_ACCEPT: bool = true;
-const ACCEPT_DELAY: u32 = 2000;
-const MULTI_PROVIDER: &str = "opgg"; // Fixed to op.gg as requested
+const MULTI_PROVIDER: &str = "opgg"; // Default site, can be overridden per queue
+// An armed dodge fires this close to the end of finalization
+const DODGE_WINDOW_MS: u64 = 3000;
+const POLL_INTERVAL_MS: u64 = 1000;
+// Champ-select names and positions fill in over the first seconds
+const CHAMP_SELECT_SETTLE_MS: u64 = 5000;
 
-struct LCUState {
-    pub connected: bool,
-    pub data: Option<LCUClientInfo>,
+/// Which automations run for the current queue
+struct Automations {
+    pub auto_accept: bool,
+    pub open_multi: bool,
+    pub open_lobby_multi: bool,
+    pub provider: String,
+    pub dodge: bool,
+    pub turn_alerts: bool,
 }
 
-struct DodgeState {
-    pub last_dodge: Option<u64>,
-    pub enabled: Option<u64>,
+/// State carried between polls of the gameflow phase
+struct PollState {
+    pub last_phase: String,
+    // Which automatic links were already opened so polling doesn't reopen them
+    pub premade_members: Vec<String>,
+    /// The premade whose links were opened; member changes in it only update the links
+    pub premade_party_id: Option<String>,
+    pub tft_game_id: Option<u64>,
+    pub game_data: Option<GameDataCache>,
+    pub live_view: LiveView,
+    // Off in daemon mode, where stdout goes to a log
+    pub show_live_view: bool,
+    pub phase_entered_at: Instant,
+    // Cleared once the ready check is accepted, so it is only counted once
+    pub ready_check_seen_at: Option<Instant>,
+    pub game_started_at: Option<Instant>,
+    // Our pick/ban action that was last announced
+    pub turn_action_id: Option<i64>,
+    // When the team is read and the multi-search opened after entering champ select
+    pub champ_select_start_at: Option<Instant>,
+    // When an armed dodge comes due, so the next poll isn't late for it
+    pub dodge_due_at: Option<Instant>,
+    // When the current ready check is auto-accepted; cleared once it is sent
+    pub accept_due_at: Option<Instant>,
+    // The armed dodge left this champ select, so leaving it isn't a manual dodge
+    pub champ_select_dodged: bool,
+    pub region: Option<String>,
+    pub journal: Journal,
+    pub webhooks: Webhooks,
+    pub sound: Sound,
+    pub discord: DiscordPresence,
+    #[cfg(feature = "notifications")]
+    pub notifier: Option<notifications::Notifier>,
+    // The user asked to answer this ready check themselves, so it is not auto-accepted
+    pub accept_declined: bool,
+}
+
+impl PollState {
+    /// The poll interval, shortened when an armed dodge or the auto-accept
+    /// comes due sooner
+    fn next_poll_delay(&mut self) -> Duration {
+        let interval = Duration::from_millis(POLL_INTERVAL_MS);
+        [self.dodge_due_at.take(), self.accept_due_at]
+            .into_iter()
+            .flatten()
+            .map(|due| due.saturating_duration_since(Instant::now()))
+            .fold(interval, Duration::min)
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    println!("League Reveal Console - Starting...");
-    println!("Configuration:");
-    println!("  Auto Open Multi: {}", AUTO_OPEN_MULTI);
-    println!("  Auto Accept: {}", AUTO_ACCEPT);
-    println!("  Accept Delay: {}ms", ACCEPT_DELAY);
-    println!("  Multi Provider: {}", MULTI_PROVIDER);
-    println!();
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        let result = match command {
+            Command::Journal(args) => journal::query(&args),
+            Command::SystemdUnit => service::print_systemd_unit(),
+            Command::Status(args) => commands::status(&args).await,
+            Command::Accept(args) => commands::ready_check(&args, true).await,
+            Command::Decline(args) => commands::ready_check(&args, false).await,
+            Command::Lobby(args) => commands::lobby(&args).await,
+            Command::Link(args) => commands::link(&args).await,
+            Command::Summoner(args) => commands::summoner(&args).await,
+            Command::Raw(args) => commands::raw(&args).await,
+            Command::Repl => repl::run().await,
+            Command::Codegen(args) => codegen::run(&args),
+            Command::SchemaDrift(args) => drift::run(&args).await,
+        };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let mut connected = false;
-    let mut lcu_state = LCUState {
-        connected: false,
-        data: None,
+    // Logging isn't set up until the config is loaded, so these go to stderr
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let _instance_lock = match service::InstanceLock::acquire() {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let _log_guard = match logging::init(&config.logging) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!("League Reveal Console - Starting...");
+    let browser = match Browser::from_config(config.browser.as_ref()) {
+        Ok(browser) => browser,
+        Err(e) => {
+            warn!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    info!("Configuration:");
+    info!("  Auto Open Multi: {}", AUTO_OPEN_MULTI);
+    info!("  Auto Accept: {}", AUTO_ACCEPT);
+    info!("  Accept Delay: {}ms", config.ready_check.accept_delay_ms);
+    info!("  Multi Provider: {}", MULTI_PROVIDER);
+    match &config.browser {
+        Some(browser_config) => info!(
+            "  Browser: {} {}",
+            browser_config.executable,
+            browser_config.args.join(" ")
+        ),
+        None => info!("  Browser: system default"),
+    }
+
+    let app_state = state::new_shared_state();
+    if config.api.enabled {
+        api::start(&config, app_state.clone());
+    }
+    if config.metrics.enabled {
+        metrics::start(&config.metrics);
+    }
+    // Listen for signals before the terminal UI takes over the terminal, so
+    // none of them can end the process without it being restored
+    let reload = service::reload_on_hangup();
+    let shutdown = service::shutdown_signal();
+    // Quitting the terminal UI shuts down the same way as a signal
+    let (quit, quit_requested) = tokio::sync::oneshot::channel();
+    #[cfg(feature = "tui")]
+    let tui = (!cli.daemon)
+        .then(|| tui::start(app_state.clone(), quit))
+        .flatten();
+    #[cfg(feature = "tui")]
+    if tui.is_some() {
+        console::capture_into(app_state.clone());
+    }
+    #[cfg(not(feature = "tui"))]
+    drop(quit);
+
+    #[cfg(feature = "notifications")]
+    let notifier = if config.notifications.enabled {
+        match notifications::Notifier::connect(&config.notifications, app_state.clone()).await {
+            Ok(notifier) => Some(notifier),
+            Err(e) => {
+                warn!("Desktop notifications unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
     };
-    let _dodge_state = Mutex::new(DodgeState {
-        last_dodge: None,
-        enabled: None,
-    });
 
+    let poll_state = PollState {
+        last_phase: String::new(),
+        premade_members: Vec::new(),
+        premade_party_id: None,
+        tft_game_id: None,
+        game_data: None,
+        live_view: LiveView::default(),
+        show_live_view: !cli.daemon,
+        phase_entered_at: Instant::now(),
+        ready_check_seen_at: None,
+        game_started_at: None,
+        turn_action_id: None,
+        champ_select_start_at: None,
+        dodge_due_at: None,
+        accept_due_at: None,
+        champ_select_dodged: false,
+        region: None,
+        journal: Journal::open(&config.journal),
+        webhooks: Webhooks::new(&config.webhooks),
+        sound: Sound::new(&config.sound),
+        discord: DiscordPresence::start(&config.discord),
+        #[cfg(feature = "notifications")]
+        notifier,
+        accept_declined: false,
+    };
+
+    tokio::select! {
+        _ = run(config, browser, poll_state, &app_state, reload) => {}
+        signal = shutdown => info!("{} received, shutting down", signal),
+        // Without a terminal UI the sender is dropped, which disables this branch
+        Ok(()) = quit_requested => info!("Quit from the terminal UI, shutting down"),
+    }
+    #[cfg(feature = "tui")]
+    if let Some(tui) = tui {
+        tui.stop();
+    }
+    // Dropping the poll loop cancelled any pending auto-accept, and returning
+    // drops the log guard, which flushes the log file
+    info!("Stopped");
+}
+
+/// Connects to the League Client and polls it until the process is stopped
+async fn run(
+    mut config: Config,
+    mut browser: Browser,
+    mut poll_state: PollState,
+    app_state: &SharedState,
+    reload: Arc<AtomicBool>,
+) {
+    let mut connected = false;
+    let mut connected_before = false;
     loop {
+        if reload.swap(false, Ordering::Relaxed) {
+            reload_config(&mut config, &mut browser, &mut poll_state);
+        }
         let lcu_info = match lcu_client::find_league_client().await {
             Some(info) => info,
             None => {
                 if connected {
-                    println!("Waiting for League Client to open...");
+                    info!("Waiting for League Client to open...");
                     connected = false;
-                    lcu_state.connected = false;
+                    app_state.lock().unwrap().set_connected(false, None);
                 }
                 tokio::time::sleep(Duration::from_secs(2)).await;
                 continue;
             }
         };
 
-        let app_client = match LCUClient::new(&lcu_info, false).await {
+        let app_client = match LCUClient::new(&lcu_info).await {
             Ok(client) => client,
             Err(e) => {
-                println!("Failed to create app client: {:?}", e);
+                warn!("Failed to create app client: {}", e);
                 tokio::time::sleep(Duration::from_secs(2)).await;
                 continue;
             }
         };
 
-        let remoting_client = match LCUClient::new(&lcu_info, true).await {
+        let remoting_client = match LCUClient::new(&lcu_info).await {
             Ok(client) => client,
             Err(e) => {
-                println!("Failed to create remoting client: {:?}", e);
+                warn!("Failed to create remoting client: {}", e);
                 tokio::time::sleep(Duration::from_secs(2)).await;
                 continue;
             }
         };
 
         connected = true;
-        lcu_state.connected = true;
-        lcu_state.data = Some(lcu_info);
+        if connected_before {
+            metrics::metrics().reconnects.inc();
+        }
+        connected_before = true;
+        debug!(
+            "League Client on port {} (token {})",
+            lcu_info.port,
+            logging::secret(&lcu_info.password)
+        );
+        app_state.lock().unwrap().set_connected(true, Some(lcu_info));
 
-        println!("Connected to League Client!");
+        info!("Connected to League Client!");
+        let region_info = region::get_region_info(&app_client).await;
+        let summoner = summoner::get_current_summoner(&remoting_client).await;
+        poll_state.region = region_info.map(|info| info.region);
+        poll_state.journal.set_account(summoner.as_ref());
+        poll_state.last_phase.clear();
+        game_data::refresh(&mut poll_state.game_data, &remoting_client).await;
 
         // Handle initial state
         let state = get_gameflow_state(&remoting_client).await;
-        handle_client_state(state, &remoting_client, &app_client).await;
+        handle_client_state(
+            state,
+            &remoting_client,
+            &app_client,
+            &config,
+            &browser,
+            &mut poll_state,
+            app_state,
+        )
+        .await;
 
         // Simple polling loop instead of websockets for now
         loop {
-            tokio::time::sleep(Duration::from_secs(1)).await;
-            
+            tokio::time::sleep(poll_state.next_poll_delay()).await;
+            if reload.swap(false, Ordering::Relaxed) {
+                reload_config(&mut config, &mut browser, &mut poll_state);
+            }
+
             // Check if client is still running
             if lcu_client::find_league_client().await.is_none() {
-                println!("League Client disconnected");
+                info!("League Client disconnected");
+                app_state.lock().unwrap().set_connected(false, None);
+                poll_state.discord.clear();
                 break;
             }
 
             // Check gameflow state
             let state = get_gameflow_state(&remoting_client).await;
-            handle_client_state(state, &remoting_client, &app_client).await;
+            handle_client_state(
+                state,
+                &remoting_client,
+                &app_client,
+                &config,
+                &browser,
+                &mut poll_state,
+                app_state,
+            )
+            .await;
         }
     }
 }
 
+/// SIGHUP: applies the settings that are read while polling. On an invalid
+/// config file the current settings are kept.
+fn reload_config(config: &mut Config, browser: &mut Browser, poll_state: &mut PollState) {
+    let reloaded = match config::load_config() {
+        Ok(reloaded) => reloaded,
+        Err(e) => {
+            warn!("Config not reloaded: {}", e);
+            return;
+        }
+    };
+    match Browser::from_config(reloaded.browser.as_ref()) {
+        Ok(reloaded_browser) => *browser = reloaded_browser,
+        Err(e) => {
+            warn!("Config not reloaded: {}", e);
+            return;
+        }
+    }
+    poll_state.webhooks = Webhooks::new(&reloaded.webhooks);
+    poll_state.sound = Sound::new(&reloaded.sound);
+    if reloaded.discord != config.discord {
+        poll_state.discord = DiscordPresence::start(&reloaded.discord);
+    }
+    *config = reloaded;
+    info!("Config reloaded; [logging], [api], [overlay], [metrics], [journal] and [notifications] apply after a restart");
+}
+
 async fn get_gameflow_state(remoting_client: &LCUClient) -> String {
     match remoting_client.get("/lol-gameflow/v1/gameflow-phase").await {
         Ok(response) => {
@@ -121,65 +421,601 @@ async fn handle_client_state(
     client_state: String,
     remoting_client: &LCUClient,
     app_client: &LCUClient,
+    config: &Config,
+    browser: &Browser,
+    poll_state: &mut PollState,
+    app_state: &SharedState,
 ) {
+    let phase_changed = poll_state.last_phase != client_state;
+    let mut left_champ_select = false;
+    if phase_changed {
+        info!("Client State Update: {}", client_state);
+        let mut app_state = app_state.lock().unwrap();
+        app_state.record_phase(&client_state);
+        if poll_state.last_phase == "ChampSelect" {
+            // An armed dodge only applies to the champ select it was armed in
+            app_state.dodge.enabled = None;
+            app_state.set_champ_select(Vec::new(), None);
+            poll_state.champ_select_start_at = None;
+            poll_state.dodge_due_at = None;
+            // Back in the lobby means we quit; when someone else dodges the
+            // client puts us back in the queue instead
+            left_champ_select = !std::mem::take(&mut poll_state.champ_select_dodged)
+                && matches!(client_state.as_str(), "Lobby" | "None");
+        }
+        record_phase_metrics(&client_state, poll_state);
+        poll_state.accept_declined = false;
+        // The full delay is left to skip the auto-accept, which is sent once
+        poll_state.accept_due_at = (client_state == "ReadyCheck").then(|| {
+            Instant::now() + Duration::from_millis(config.ready_check.accept_delay_ms)
+        });
+        poll_state.last_phase = client_state.clone();
+    }
+
+    let (reopen_requested, multi_search_requested, skip_accept_requested) = {
+        let mut app_state = app_state.lock().unwrap();
+        (
+            std::mem::take(&mut app_state.reopen_requested),
+            std::mem::take(&mut app_state.multi_search_requested),
+            std::mem::take(&mut app_state.skip_accept_requested),
+        )
+    };
+    if skip_accept_requested && client_state == "ReadyCheck" {
+        skip_auto_accept(poll_state, app_state);
+    }
+
+    let session = gameflow::get_gameflow_session(remoting_client).await;
+    if phase_changed {
+        if left_champ_select {
+            info!("Left champ select");
+            app_state
+                .lock()
+                .unwrap()
+                .record_action("Left champ select".to_string());
+            dispatch_event(GameEvent::Dodged, session.as_ref(), poll_state, None);
+        }
+        dispatch_phase_events(&client_state, session.as_ref(), poll_state);
+        poll_state
+            .discord
+            .update(&client_state, session.as_ref(), poll_state.game_data.as_ref());
+    }
+    let is_tft = session.as_ref().is_some_and(|s| s.is_tft());
+    let mut automations = resolve_automations(config, session.as_ref());
+    {
+        let app_state = app_state.lock().unwrap();
+        automations.auto_accept &= app_state.auto_accept_enabled;
+        if app_state.automations_paused {
+            automations.auto_accept = false;
+            automations.open_multi = false;
+            automations.open_lobby_multi = false;
+        }
+    }
+
+    let mut opened_links = Vec::new();
+    if multi_search_requested && client_state == "ChampSelect" && !phase_changed {
+        // A fresh search, since picks may have been swapped since the last one
+        let (champ_select, team) = get_champ_select_team(app_client, remoting_client, config).await;
+        if let Some(region_info) = region::get_region_info(app_client).await {
+            opened_links = open_champ_select_multi(
+                &team,
+                champ_select.as_ref(),
+                session.as_ref(),
+                region_info.multi_region(),
+                &automations.provider,
+                browser,
+            );
+        }
+    } else if reopen_requested || multi_search_requested {
+        let last_links = app_state.lock().unwrap().last_links.clone();
+        for link in &last_links {
+            info!("Reopening link: {}", logging::url(link));
+            browser.open(link);
+        }
+    }
+
     match client_state.as_str() {
+        "Lobby" if automations.open_lobby_multi => {
+            opened_links = handle_lobby_update(
+                app_client,
+                remoting_client,
+                config,
+                browser,
+                is_tft,
+                &automations.provider,
+                poll_state,
+                app_state,
+            )
+            .await;
+        }
         "ChampSelect" => {
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            handle_champ_select_start(app_client, remoting_client).await;
+            // Waiting for the team to settle doesn't hold up the board or an armed dodge
+            if phase_changed {
+                poll_state.champ_select_start_at =
+                    Some(Instant::now() + Duration::from_millis(CHAMP_SELECT_SETTLE_MS));
+            }
+            let settled = poll_state
+                .champ_select_start_at
+                .is_some_and(|at| at <= Instant::now());
+            if settled {
+                poll_state.champ_select_start_at = None;
+                game_data::refresh(&mut poll_state.game_data, remoting_client).await;
+                opened_links = handle_champ_select_start(
+                    app_client,
+                    remoting_client,
+                    config,
+                    browser,
+                    session.as_ref(),
+                    &automations,
+                    poll_state.game_data.as_ref(),
+                )
+                .await;
+                poll_state.live_view.reset();
+            }
+            if let Some(champ_select) = champ_select::get_champ_select_session(remoting_client).await {
+                app_state.lock().unwrap().set_champ_select(
+                    live_view::board(&champ_select, poll_state.game_data.as_ref()),
+                    Some(live_view::snapshot(&champ_select, poll_state.game_data.as_ref())),
+                );
+                // The dashboard draws the board itself
+                if poll_state.show_live_view && !console::is_captured() {
+                    poll_state
+                        .live_view
+                        .render(&champ_select, poll_state.game_data.as_ref());
+                }
+                let turn = champ_select
+                    .local_player_action()
+                    .map(|action| (action.id, &action.action_type));
+                if let Some((action_id, action_type)) = turn {
+                    if poll_state.turn_action_id != Some(action_id) && automations.turn_alerts {
+                        let event = match action_type {
+                            champ_select::Type::Ban => GameEvent::BanTurn,
+                            _ => GameEvent::PickTurn,
+                        };
+                        dispatch_event(event, session.as_ref(), poll_state, None);
+                    }
+                    poll_state.turn_action_id = Some(action_id);
+                }
+                #[cfg(feature = "notifications")]
+                if let Some(notifier) = &poll_state.notifier {
+                    let armed = app_state.lock().unwrap().dodge.enabled.is_some();
+                    if armed && automations.dodge && champ_select.timer.phase == "FINALIZATION" {
+                        let until_dodge = champ_select
+                            .timer
+                            .adjusted_time_left_in_phase
+                            .saturating_sub(DODGE_WINDOW_MS);
+                        notifier.dodge_pending(until_dodge / 1000);
+                    }
+                }
+                let dodged =
+                    dodge_if_armed(remoting_client, &champ_select, app_state, automations.dodge)
+                        .await;
+                if dodged {
+                    poll_state.champ_select_dodged = true;
+                    dispatch_event(GameEvent::Dodged, session.as_ref(), poll_state, None);
+                } else {
+                    poll_state.dodge_due_at = dodge_due_at(&champ_select, app_state);
+                }
+            }
         }
-        "ReadyCheck" => {
-            if AUTO_ACCEPT {
-                tokio::time::sleep(std::time::Duration::from_millis(
-                    (ACCEPT_DELAY as u64) - 1000,
-                ))
+        "InProgress" if is_tft && automations.open_multi => {
+            if let Some(session) = &session {
+                opened_links = handle_tft_game_start(
+                    app_client,
+                    remoting_client,
+                    config,
+                    browser,
+                    session,
+                    poll_state,
+                )
+                .await;
+            }
+        }
+        "ReadyCheck"
+            if poll_state.ready_check_seen_at.is_some()
+                && accepted_manually(remoting_client).await =>
+        {
+            // Accepted in the client before (or instead of) the auto-accept
+            poll_state.ready_check_seen_at = None;
+            info!("Ready check accepted");
+            dispatch_event(GameEvent::Accepted, session.as_ref(), poll_state, None);
+        }
+        "ReadyCheck"
+            if automations.auto_accept
+                && !poll_state.accept_declined
+                && poll_state
+                    .accept_due_at
+                    .is_some_and(|due| Instant::now() >= due) =>
+        {
+            poll_state.accept_due_at = None;
+            let resp = remoting_client
+                .post(
+                    "/lol-matchmaking/v1/ready-check/accept",
+                    serde_json::json!({}),
+                )
                 .await;
-                let _resp = remoting_client
-                    .post(
-                        "/lol-matchmaking/v1/ready-check/accept",
-                        serde_json::json!({}),
-                    )
-                    .await;
-                println!("Auto-accepted ready check");
+            if let Err(e) = resp {
+                warn!("Failed to accept ready check: {}", e);
+                return;
             }
+            if let Some(seen_at) = poll_state.ready_check_seen_at.take() {
+                let metrics = metrics::metrics();
+                metrics.ready_checks_accepted.inc();
+                metrics.time_to_accept.observe(seen_at.elapsed().as_secs_f64());
+                metrics::automation_action("accept_ready_check");
+                dispatch_event(GameEvent::Accepted, session.as_ref(), poll_state, None);
+            }
+            info!("Auto-accepted ready check");
+            app_state
+                .lock()
+                .unwrap()
+                .record_action("Accepted ready check".to_string());
         }
         _ => {}
     }
 
-    println!("Client State Update: {}", client_state);
+    if !opened_links.is_empty() {
+        metrics::automation_action("open_links");
+        let mut app_state = app_state.lock().unwrap();
+        app_state.record_action(format!("Opened {} link(s)", opened_links.len()));
+        app_state.record_links(opened_links);
+    }
 }
 
-async fn handle_champ_select_start(
-    app_client: &LCUClient,
-    remoting_client: &LCUClient,
+/// Called on a phase change, before `last_phase` is updated
+fn record_phase_metrics(phase: &str, poll_state: &mut PollState) {
+    let metrics = metrics::metrics();
+    if poll_state.last_phase == "Matchmaking" {
+        metrics
+            .queue_duration
+            .observe(poll_state.phase_entered_at.elapsed().as_secs_f64());
+    }
+    match phase {
+        "ReadyCheck" => {
+            metrics.ready_checks_seen.inc();
+            poll_state.ready_check_seen_at = Some(Instant::now());
+        }
+        "ChampSelect" => metrics.champ_selects_entered.inc(),
+        _ => poll_state.ready_check_seen_at = None,
+    }
+    poll_state.phase_entered_at = Instant::now();
+}
+
+/// Events that follow from the phase alone
+fn dispatch_phase_events(phase: &str, session: Option<&GameflowSession>, poll_state: &mut PollState) {
+    let event = match phase {
+        "ReadyCheck" => GameEvent::ReadyCheck,
+        "ChampSelect" => GameEvent::ChampSelectStarted,
+        "InProgress" => {
+            poll_state.game_started_at = Some(Instant::now());
+            GameEvent::GameStarted
+        }
+        "EndOfGame" => GameEvent::GameEnded,
+        _ => return,
+    };
+    let duration = match event {
+        GameEvent::GameEnded => poll_state.game_started_at.take().map(|at| at.elapsed()),
+        _ => None,
+    };
+    dispatch_event(event, session, poll_state, duration);
+}
+
+/// Hands an event to the journal, webhooks, sound and notifications
+fn dispatch_event(
+    event: GameEvent,
+    session: Option<&GameflowSession>,
+    poll_state: &PollState,
+    duration: Option<Duration>,
 ) {
-    let team = lobby::get_lobby_info(app_client).await;
-    let region_info: RegionInfo = match app_client.get("/riotclient/region-locale").await {
-        Ok(response) => {
-            match response.json().await {
-                Ok(info) => info,
-                Err(_) => {
-                    println!("Failed to parse region info");
-                    return;
-                }
-            }
+    let payload = EventPayload::new(
+        event,
+        session,
+        poll_state.game_data.as_ref(),
+        poll_state.region.as_deref(),
+        duration,
+    );
+    poll_state.journal.record(&payload);
+    poll_state.webhooks.notify(&payload);
+    poll_state.sound.play(event);
+    #[cfg(feature = "notifications")]
+    if let Some(notifier) = &poll_state.notifier {
+        notifier.notify(&payload);
+    }
+}
+
+/// Whether our response to the current ready check is already an accept
+async fn accepted_manually(remoting_client: &LCUClient) -> bool {
+    let response = match remoting_client.get("/lol-matchmaking/v1/ready-check").await {
+        Ok(response) => response,
+        Err(_) => return false,
+    };
+    let ready_check: serde_json::Value = match response.json().await {
+        Ok(ready_check) => ready_check,
+        Err(_) => return false,
+    };
+    ready_check["playerResponse"] == "Accepted"
+}
+
+/// Leaves the current ready check for the user to answer in the client.
+/// Nothing is sent, so it is never declined on their behalf.
+fn skip_auto_accept(poll_state: &mut PollState, app_state: &SharedState) {
+    poll_state.accept_declined = true;
+    info!("Not auto-accepting this ready check");
+    app_state
+        .lock()
+        .unwrap()
+        .record_action("Skipped auto-accept".to_string());
+}
+
+/// When an armed dodge should fire, from the time left in finalization
+fn dodge_due_at(champ_select: &ChampSelectSession, app_state: &SharedState) -> Option<Instant> {
+    if app_state.lock().unwrap().dodge.enabled.is_none()
+        || champ_select.timer.phase != "FINALIZATION"
+    {
+        return None;
+    }
+    let until_window = champ_select
+        .timer
+        .adjusted_time_left_in_phase
+        .saturating_sub(DODGE_WINDOW_MS);
+    Some(Instant::now() + Duration::from_millis(until_window))
+}
+
+/// Quits champ select in the last moments of finalization when a dodge is
+/// armed and the queue allows it. Returns whether it dodged.
+async fn dodge_if_armed(
+    remoting_client: &LCUClient,
+    champ_select: &ChampSelectSession,
+    app_state: &SharedState,
+    allowed: bool,
+) -> bool {
+    if app_state.lock().unwrap().dodge.enabled.is_none()
+        || champ_select.timer.phase != "FINALIZATION"
+        || champ_select.timer.adjusted_time_left_in_phase > DODGE_WINDOW_MS
+    {
+        return false;
+    }
+    if !allowed {
+        info!("Dodging is off for this queue, disarming");
+        app_state.lock().unwrap().dodge.enabled = None;
+        return false;
+    }
+
+    match champ_select::dodge(remoting_client).await {
+        Ok(_) => {
+            info!("Dodged champ select");
+            metrics::metrics().champ_selects_dodged.inc();
+            metrics::automation_action("dodge");
+            let mut app_state = app_state.lock().unwrap();
+            app_state.dodge.enabled = None;
+            app_state.dodge.last_dodge = Some(state::now_ms());
+            app_state.record_action("Dodged champ select".to_string());
+            true
         }
-        Err(_) => {
-            println!("Failed to get region info");
-            return;
+        Err(e) => {
+            warn!("Failed to dodge: {}", e);
+            false
+        }
+    }
+}
+
+fn resolve_automations(config: &Config, session: Option<&GameflowSession>) -> Automations {
+    let mut automations = if session.is_some_and(|s| s.is_tft()) {
+        Automations {
+            auto_accept: config.tft.auto_accept,
+            open_multi: config.tft.open_links,
+            open_lobby_multi: config.lobby.open_multi,
+            provider: MULTI_PROVIDER.to_string(),
+            dodge: true,
+            turn_alerts: true,
+        }
+    } else {
+        Automations {
+            auto_accept: AUTO_ACCEPT,
+            open_multi: AUTO_OPEN_MULTI,
+            open_lobby_multi: config.lobby.open_multi,
+            provider: MULTI_PROVIDER.to_string(),
+            dodge: true,
+            turn_alerts: true,
         }
     };
 
-    println!("Champ select started!");
+    let overrides = session.map(|s| config.queue_overrides(s)).unwrap_or_default();
+    for queue in overrides {
+        automations.auto_accept = queue.auto_accept.unwrap_or(automations.auto_accept);
+        automations.open_multi = queue.open_multi.unwrap_or(automations.open_multi);
+        automations.open_lobby_multi = queue
+            .open_lobby_multi
+            .unwrap_or(automations.open_lobby_multi);
+        if let Some(provider) = &queue.provider {
+            automations.provider = provider.clone();
+        }
+        automations.dodge = queue.dodge.unwrap_or(automations.dodge);
+        automations.turn_alerts = queue.turn_alerts.unwrap_or(automations.turn_alerts);
+    }
+
+    automations
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_lobby_update(
+    app_client: &LCUClient,
+    remoting_client: &LCUClient,
+    config: &Config,
+    browser: &Browser,
+    is_tft: bool,
+    provider: &str,
+    poll_state: &mut PollState,
+    app_state: &SharedState,
+) -> Vec<String> {
+    let party = match lobby::get_party_lobby(remoting_client).await {
+        Some(party) => party,
+        None => return Vec::new(),
+    };
 
-    if AUTO_OPEN_MULTI {
-        let region = match region_info.web_region.as_str() {
-            "SG2" => "SG",
-            _ => &region_info.web_region,
+    // Only a lobby with someone besides us counts as a premade
+    if party.members.len() < 2 {
+        poll_state.premade_members.clear();
+        poll_state.premade_party_id = None;
+        return Vec::new();
+    }
+
+    let mut members: Vec<String> = party.members.iter().map(|m| m.puuid.clone()).collect();
+    members.sort();
+    if members == poll_state.premade_members {
+        return Vec::new();
+    }
+    poll_state.premade_members = members;
+    let formed = poll_state.premade_party_id.as_deref() != Some(party.party_id.as_str());
+    poll_state.premade_party_id = Some(party.party_id.clone());
+
+    let region_info = match region::get_region_info(app_client).await {
+        Some(info) => info,
+        None => return Vec::new(),
+    };
+
+    let team =
+        lobby::get_party_participants(remoting_client, &party, config.multi_search.exclude_self)
+            .await;
+    // Someone joining or leaving doesn't open another tab; reopening shows the new lineup
+    if !formed {
+        info!("Premade lobby changed: {}", utils::format_team(&team));
+        let links = if is_tft {
+            utils::tft_links(&team, &region_info, config.tft.provider)
+        } else if team.participants.is_empty() {
+            Vec::new()
+        } else {
+            vec![utils::multi_search_link(&team, region_info.multi_region(), provider)]
         };
+        app_state.lock().unwrap().record_links(links);
+        return Vec::new();
+    }
 
-        display_champ_select(&team, region, MULTI_PROVIDER);
+    info!("Premade lobby formed!");
+    if is_tft {
+        display_tft_links(&team, &region_info, config.tft.provider, browser)
+    } else {
+        display_champ_select(&team, region_info.multi_region(), provider, browser)
+            .into_iter()
+            .collect()
     }
+}
 
+async fn handle_tft_game_start(
+    app_client: &LCUClient,
+    remoting_client: &LCUClient,
+    config: &Config,
+    browser: &Browser,
+    session: &GameflowSession,
+    poll_state: &mut PollState,
+) -> Vec<String> {
+    let game_id = session.game_data.game_id;
+    if poll_state.tft_game_id == Some(game_id) {
+        return Vec::new();
+    }
+    poll_state.tft_game_id = Some(game_id);
+
+    let region_info = match region::get_region_info(app_client).await {
+        Some(info) => info,
+        None => return Vec::new(),
+    };
+
+    info!("TFT game started!");
     let summoner = summoner::get_current_summoner(remoting_client).await;
-    analytics::send_analytics_event(&team, &summoner, &region_info).await;
-}
\ No newline at end of file
+    let players = lobby::get_game_participants(
+        remoting_client,
+        &session.player_puuids(),
+        summoner.as_ref().map(|summoner| summoner.puuid.as_str()),
+    )
+    .await;
+    display_tft_links(&players, &region_info, config.tft.provider, browser)
+}
+
+async fn handle_champ_select_start(
+    app_client: &LCUClient,
+    remoting_client: &LCUClient,
+    config: &Config,
+    browser: &Browser,
+    gameflow_session: Option<&GameflowSession>,
+    automations: &Automations,
+    game_data: Option<&GameDataCache>,
+) -> Vec<String> {
+    let (session, team) = get_champ_select_team(app_client, remoting_client, config).await;
+    let region_info = match region::get_region_info(app_client).await {
+        Some(info) => info,
+        None => return Vec::new(),
+    };
+
+    match gameflow_session.and_then(|s| describe_queue(s, game_data?)) {
+        Some(queue) => info!("Champ select started! ({})", queue),
+        None => info!("Champ select started!"),
+    }
+
+    if let (Some(session), Some(game_data)) = (&session, game_data) {
+        if let Some(me) = session.local_player() {
+            info!("You: {}", live_view::describe_cell(me, game_data));
+        }
+    }
+
+    let mut links = Vec::new();
+    if automations.open_multi {
+        links = open_champ_select_multi(
+            &team,
+            session.as_ref(),
+            gameflow_session,
+            region_info.multi_region(),
+            &automations.provider,
+            browser,
+        );
+    }
+
+    links
+}
+
+/// The champ-select session and the players to search for, in role order
+async fn get_champ_select_team(
+    app_client: &LCUClient,
+    remoting_client: &LCUClient,
+    config: &Config,
+) -> (Option<ChampSelectSession>, Lobby) {
+    let session = champ_select::get_champ_select_session(remoting_client).await;
+    let mut team = lobby::get_lobby_info(
+        app_client,
+        session.as_ref(),
+        config.multi_search.exclude_self,
+    )
+    .await;
+    if let Some(session) = &session {
+        lobby::order_by_position(&mut team, session);
+    }
+    (session, team)
+}
+
+fn open_champ_select_multi(
+    team: &Lobby,
+    session: Option<&ChampSelectSession>,
+    gameflow_session: Option<&GameflowSession>,
+    region: &str,
+    provider: &str,
+    browser: &Browser,
+) -> Vec<String> {
+    let is_arena = gameflow_session.is_some_and(|s| s.is_arena());
+    let link = match session {
+        Some(session) if is_arena => {
+            let (duo, others) = lobby::split_arena_duo(team, session);
+            display_arena_champ_select(&duo, &others, region, provider, browser)
+        }
+        _ => display_champ_select(team, region, provider, browser),
+    };
+    link.into_iter().collect()
+}
+
+fn describe_queue(session: &GameflowSession, game_data: &GameDataCache) -> Option<String> {
+    let queue = game_data.queue_name(session.queue_id());
+    let map = game_data.map_name(session.map_id());
+    match (queue, map) {
+        (Some(queue), Some(map)) => Some(format!("{} on {}", queue, map)),
+        (Some(queue), None) => Some(queue.to_string()),
+        (None, Some(map)) => Some(map.to_string()),
+        (None, None) => None,
+    }
+}
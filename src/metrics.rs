@@ -0,0 +1,223 @@
+use crate::config::MetricsConfig;
+use crate::lcu_client::LcuError;
+use tracing::{info, warn};
+use axum::http::header;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const NAMESPACE: &str = "league_reveal";
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub struct Metrics {
+    registry: Registry,
+    pub reconnects: IntCounter,
+    pub lcu_request_duration: HistogramVec,
+    pub lcu_errors: IntCounterVec,
+    pub ready_checks_seen: IntCounter,
+    pub ready_checks_accepted: IntCounter,
+    pub time_to_accept: Histogram,
+    pub queue_duration: Histogram,
+    pub champ_selects_entered: IntCounter,
+    pub champ_selects_dodged: IntCounter,
+    pub automation_actions: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let metrics = Metrics {
+            reconnects: IntCounter::with_opts(opts(
+                "reconnects_total",
+                "Connections to the League Client after the first one",
+            ))
+            .unwrap(),
+            lcu_request_duration: HistogramVec::new(
+                histogram_opts(
+                    "lcu_request_duration_seconds",
+                    "League Client API request latency",
+                    vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5],
+                ),
+                &["endpoint"],
+            )
+            .unwrap(),
+            lcu_errors: IntCounterVec::new(
+                opts("lcu_errors_total", "Failed League Client API requests"),
+                &["kind"],
+            )
+            .unwrap(),
+            ready_checks_seen: IntCounter::with_opts(opts(
+                "ready_checks_seen_total",
+                "Ready checks shown by the client",
+            ))
+            .unwrap(),
+            ready_checks_accepted: IntCounter::with_opts(opts(
+                "ready_checks_accepted_total",
+                "Ready checks accepted automatically",
+            ))
+            .unwrap(),
+            time_to_accept: Histogram::with_opts(histogram_opts(
+                "time_to_accept_seconds",
+                "Time from a ready check appearing to accepting it",
+                vec![0.5, 1.0, 1.5, 2.0, 3.0, 5.0, 8.0, 12.0],
+            ))
+            .unwrap(),
+            queue_duration: Histogram::with_opts(histogram_opts(
+                "queue_duration_seconds",
+                "Time spent in matchmaking per queue attempt",
+                vec![15.0, 30.0, 60.0, 120.0, 180.0, 300.0, 600.0, 900.0],
+            ))
+            .unwrap(),
+            champ_selects_entered: IntCounter::with_opts(opts(
+                "champ_selects_entered_total",
+                "Champ selects entered",
+            ))
+            .unwrap(),
+            champ_selects_dodged: IntCounter::with_opts(opts(
+                "champ_selects_dodged_total",
+                "Champ selects left through an armed dodge",
+            ))
+            .unwrap(),
+            automation_actions: IntCounterVec::new(
+                opts("automation_actions_total", "Actions taken automatically"),
+                &["action"],
+            )
+            .unwrap(),
+            registry,
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 10] = [
+            Box::new(metrics.reconnects.clone()),
+            Box::new(metrics.lcu_request_duration.clone()),
+            Box::new(metrics.lcu_errors.clone()),
+            Box::new(metrics.ready_checks_seen.clone()),
+            Box::new(metrics.ready_checks_accepted.clone()),
+            Box::new(metrics.time_to_accept.clone()),
+            Box::new(metrics.queue_duration.clone()),
+            Box::new(metrics.champ_selects_entered.clone()),
+            Box::new(metrics.champ_selects_dodged.clone()),
+            Box::new(metrics.automation_actions.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).unwrap();
+        }
+        metrics
+    }
+}
+
+fn opts(name: &str, help: &str) -> Opts {
+    Opts::new(name, help).namespace(NAMESPACE)
+}
+
+fn histogram_opts(name: &str, help: &str, buckets: Vec<f64>) -> HistogramOpts {
+    HistogramOpts::new(name, help)
+        .namespace(NAMESPACE)
+        .buckets(buckets)
+}
+
+/// Always available, whether or not the endpoint is served
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+pub fn observe_lcu_request(endpoint: &str, elapsed: Duration, error: Option<&LcuError>) {
+    let metrics = metrics();
+    metrics
+        .lcu_request_duration
+        .with_label_values(&[&endpoint_label(endpoint)])
+        .observe(elapsed.as_secs_f64());
+    if let Some(error) = error.filter(|error| !error.is_not_found()) {
+        metrics.lcu_errors.with_label_values(&[error.kind()]).inc();
+    }
+}
+
+pub fn automation_action(action: &str) {
+    metrics().automation_actions.with_label_values(&[action]).inc();
+}
+
+/// Drops the query string and replaces ids (numbers, puuids) with `{id}` so
+/// every player doesn't get their own time series
+fn endpoint_label(endpoint: &str) -> String {
+    let path = endpoint.split('?').next().unwrap_or(endpoint);
+    path.split('/')
+        .map(|segment| {
+            let is_id = segment.chars().any(|c| c.is_ascii_digit())
+                && (segment.chars().all(|c| c.is_ascii_digit()) || segment.len() >= 32);
+            if is_id {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Serves `/metrics` for Prometheus in the background
+pub fn start(metrics_config: &MetricsConfig) {
+    let addr = metrics_config.listen;
+    let router = Router::new().route("/metrics", get(|| async { render() }));
+
+    tokio::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Failed to start metrics endpoint on {}: {}", addr, e);
+                return;
+            }
+        };
+        info!("Metrics available on http://{}/metrics", addr);
+        if let Err(e) = axum::serve(listener, router).await {
+            warn!("Metrics endpoint stopped: {}", e);
+        }
+    });
+}
+
+fn render() -> ([(header::HeaderName, &'static str); 1], String) {
+    let body = TextEncoder::new()
+        .encode_to_string(&metrics().registry.gather())
+        .unwrap_or_default();
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn status(status: StatusCode) -> LcuError {
+        LcuError::Status {
+            endpoint: "/lol-champ-select/v1/session".to_string(),
+            status,
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn not_found_is_not_an_error() {
+        let errors = metrics().lcu_errors.with_label_values(&["status"]);
+        let before = errors.get();
+
+        observe_lcu_request(
+            "/lol-champ-select/v1/session",
+            Duration::ZERO,
+            Some(&status(StatusCode::NOT_FOUND)),
+        );
+        assert_eq!(errors.get(), before);
+
+        observe_lcu_request(
+            "/lol-champ-select/v1/session",
+            Duration::ZERO,
+            Some(&status(StatusCode::INTERNAL_SERVER_ERROR)),
+        );
+        assert_eq!(errors.get(), before + 1);
+    }
+}
@@ -0,0 +1,406 @@
+use crate::config::NotificationsConfig;
+use crate::events::{EventPayload, GameEvent};
+use crate::state::SharedState;
+use futures_util::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+use zbus::zvariant::Value;
+
+const APP_NAME: &str = "League Reveal Console";
+const SKIP_ACCEPT_ACTION: &str = "skip_accept";
+const CANCEL_DODGE_ACTION: &str = "cancel-dodge";
+// A ready check is gone after about 12 seconds, so is its notification
+const READY_CHECK_TIMEOUT_MS: i32 = 12_000;
+// -1 lets the notification server decide
+const DEFAULT_TIMEOUT_MS: i32 = -1;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, &Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: String) -> zbus::Result<()>;
+}
+
+/// `urgency` hint values from the notification spec
+#[derive(Debug, Clone, Copy)]
+enum Urgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+struct Notice {
+    summary: String,
+    body: String,
+    urgency: Urgency,
+    /// Key and label pairs
+    actions: &'static [&'static str],
+    timeout_ms: i32,
+}
+
+/// Shows desktop notifications through `org.freedesktop.Notifications`
+pub struct Notifier {
+    proxy: NotificationsProxy<'static>,
+    events: Vec<GameEvent>,
+    /// Our notifications, since every client on the bus sees every `ActionInvoked`
+    shown: Arc<Mutex<HashSet<u32>>>,
+    /// Replaced by the "accepted" notification so they don't pile up
+    ready_check_id: Arc<Mutex<Option<u32>>>,
+    dodge_announced: AtomicBool,
+}
+
+impl Notifier {
+    /// Connects to the session bus and starts listening for notification actions
+    pub async fn connect(config: &NotificationsConfig, app_state: SharedState) -> zbus::Result<Self> {
+        let connection = zbus::Connection::session().await?;
+        let proxy = NotificationsProxy::new(&connection).await?;
+        Notifier::with_proxy(proxy, config, app_state).await
+    }
+
+    async fn with_proxy(
+        proxy: NotificationsProxy<'static>,
+        config: &NotificationsConfig,
+        app_state: SharedState,
+    ) -> zbus::Result<Self> {
+        let shown = Arc::new(Mutex::new(HashSet::new()));
+        let mut actions = proxy.receive_action_invoked().await?;
+        let ours = shown.clone();
+        tokio::spawn(async move {
+            while let Some(signal) = actions.next().await {
+                let Ok(args) = signal.args() else { continue };
+                if ours.lock().unwrap().remove(&args.id) {
+                    handle_action(&args.action_key, &app_state);
+                }
+            }
+        });
+
+        Ok(Notifier {
+            proxy,
+            events: config.events.clone(),
+            shown,
+            ready_check_id: Arc::new(Mutex::new(None)),
+            dodge_announced: AtomicBool::new(false),
+        })
+    }
+
+    pub fn notify(&self, payload: &EventPayload) {
+        if payload.event == GameEvent::ChampSelectStarted {
+            self.dodge_announced.store(false, Ordering::Relaxed);
+        }
+        if !self.events.contains(&payload.event) {
+            return;
+        }
+        if let Some(notice) = notice(payload) {
+            self.show(payload.event, notice);
+        }
+    }
+
+    /// Warns once per champ select that an armed dodge is about to fire
+    pub fn dodge_pending(&self, seconds_left: u64) {
+        if !self.events.contains(&GameEvent::Dodged)
+            || self.dodge_announced.swap(true, Ordering::Relaxed)
+        {
+            return;
+        }
+        self.show(
+            GameEvent::Dodged,
+            Notice {
+                summary: format!("Dodging in {}s", seconds_left),
+                body: "An armed dodge leaves champ select at the end of finalization".to_string(),
+                urgency: Urgency::Critical,
+                actions: &[CANCEL_DODGE_ACTION, "Cancel dodge"],
+                timeout_ms: (seconds_left * 1000).min(i32::MAX as u64) as i32,
+            },
+        );
+    }
+
+    /// Sends in the background so a slow notification server never holds up polling
+    fn show(&self, event: GameEvent, notice: Notice) {
+        let proxy = self.proxy.clone();
+        let shown = self.shown.clone();
+        let ready_check_id = self.ready_check_id.clone();
+        tokio::spawn(async move {
+            let replaces_id = match event {
+                GameEvent::Accepted => ready_check_id.lock().unwrap().take().unwrap_or(0),
+                _ => 0,
+            };
+            let urgency = Value::U8(notice.urgency as u8);
+            let hints = HashMap::from([("urgency", &urgency)]);
+            let result = proxy
+                .notify(
+                    APP_NAME,
+                    replaces_id,
+                    "",
+                    &notice.summary,
+                    &notice.body,
+                    notice.actions,
+                    hints,
+                    notice.timeout_ms,
+                )
+                .await;
+            match result {
+                Ok(id) => {
+                    debug!("Notification {} shown: {}", id, notice.summary);
+                    if !notice.actions.is_empty() {
+                        shown.lock().unwrap().insert(id);
+                    }
+                    if event == GameEvent::ReadyCheck {
+                        *ready_check_id.lock().unwrap() = Some(id);
+                    }
+                }
+                Err(e) => warn!("Failed to show notification: {}", e),
+            }
+        });
+    }
+}
+
+fn notice(payload: &EventPayload) -> Option<Notice> {
+    let queue = payload.queue.clone().unwrap_or_default();
+    let notice = match payload.event {
+        GameEvent::ReadyCheck => Notice {
+            summary: "Ready check".to_string(),
+            body: queue,
+            urgency: Urgency::Critical,
+            actions: &[SKIP_ACCEPT_ACTION, "Don't accept"],
+            timeout_ms: READY_CHECK_TIMEOUT_MS,
+        },
+        GameEvent::Accepted => Notice {
+            summary: "Ready check accepted".to_string(),
+            body: queue,
+            urgency: Urgency::Low,
+            actions: &[],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        },
+        GameEvent::PickTurn => Notice {
+            summary: "Your turn to pick".to_string(),
+            body: queue,
+            urgency: Urgency::Critical,
+            actions: &[],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        },
+        GameEvent::BanTurn => Notice {
+            summary: "Your turn to ban".to_string(),
+            body: queue,
+            urgency: Urgency::Normal,
+            actions: &[],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        },
+        GameEvent::Dodged => Notice {
+            summary: "Dodged champ select".to_string(),
+            body: queue,
+            urgency: Urgency::Normal,
+            actions: &[],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+        },
+        GameEvent::ChampSelectStarted | GameEvent::GameStarted | GameEvent::GameEnded => {
+            return None
+        }
+    };
+    Some(notice)
+}
+
+fn handle_action(action: &str, app_state: &SharedState) {
+    let mut app_state = app_state.lock().unwrap();
+    match action {
+        SKIP_ACCEPT_ACTION => {
+            info!("Not auto-accepting the ready check, from notification");
+            app_state.skip_accept_requested = true;
+        }
+        CANCEL_DODGE_ACTION => {
+            info!("Dodge cancelled from notification");
+            app_state.dodge.enabled = None;
+            app_state.record_action("Disarmed dodge".to_string());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state;
+    use std::time::Duration;
+    use zbus::object_server::SignalContext;
+    use zbus::zvariant::OwnedValue;
+
+    const PATH: &str = "/org/freedesktop/Notifications";
+
+    #[derive(Debug)]
+    struct Received {
+        summary: String,
+        actions: Vec<String>,
+        urgency: Option<u8>,
+        replaces_id: u32,
+    }
+
+    /// Stand-in notification server, registered under its unique name so it
+    /// works next to a real one
+    struct FakeServer {
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl FakeServer {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: String,
+            replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            _body: String,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let mut received = self.received.lock().unwrap();
+            received.push(Received {
+                summary,
+                actions,
+                urgency: hints.get("urgency").and_then(|v| u8::try_from(v).ok()),
+                replaces_id,
+            });
+            received.len() as u32
+        }
+
+        #[zbus(signal)]
+        async fn action_invoked(ctxt: &SignalContext<'_>, id: u32, action_key: &str) -> zbus::Result<()>;
+    }
+
+    struct Bus {
+        server: zbus::Connection,
+        received: Arc<Mutex<Vec<Received>>>,
+        notifier: Notifier,
+        app_state: SharedState,
+    }
+
+    /// `None` without a session bus, e.g. outside `dbus-run-session`, unless
+    /// `REQUIRE_DBUS` is set, so CI can't skip these by accident
+    async fn bus() -> Option<Bus> {
+        let client = match zbus::Connection::session().await {
+            Ok(client) => client,
+            Err(e) if std::env::var_os("REQUIRE_DBUS").is_some() => {
+                panic!("REQUIRE_DBUS is set but there is no session bus: {}", e)
+            }
+            Err(e) => {
+                eprintln!("Skipping, no session bus: {}", e);
+                return None;
+            }
+        };
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let server = zbus::connection::Builder::session()
+            .unwrap()
+            .serve_at(PATH, FakeServer { received: received.clone() })
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let proxy = NotificationsProxy::builder(&client)
+            .destination(server.unique_name().unwrap().to_owned())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let app_state = state::new_shared_state();
+        let notifier = Notifier::with_proxy(proxy, &NotificationsConfig::default(), app_state.clone())
+            .await
+            .unwrap();
+        Some(Bus {
+            server,
+            received,
+            notifier,
+            app_state,
+        })
+    }
+
+    fn payload(event: GameEvent) -> EventPayload {
+        EventPayload::new(event, None, None, None, None)
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("timed out");
+    }
+
+    #[tokio::test]
+    async fn ready_check_is_critical_with_skip_and_replaced_on_accept() {
+        let Some(bus) = bus().await else { return };
+
+        bus.notifier.notify(&payload(GameEvent::ReadyCheck));
+        wait_for(|| bus.notifier.ready_check_id.lock().unwrap().is_some()).await;
+        bus.notifier.notify(&payload(GameEvent::Accepted));
+        wait_for(|| bus.received.lock().unwrap().len() == 2).await;
+
+        let received = bus.received.lock().unwrap();
+        assert_eq!(received[0].summary, "Ready check");
+        assert_eq!(received[0].urgency, Some(2));
+        assert_eq!(received[0].actions, ["skip_accept", "Don't accept"]);
+        assert_eq!(received[1].urgency, Some(0));
+        assert_eq!(received[1].replaces_id, 1);
+    }
+
+    #[tokio::test]
+    async fn skip_action_stops_the_auto_accept() {
+        let Some(bus) = bus().await else { return };
+
+        bus.notifier.notify(&payload(GameEvent::ReadyCheck));
+        wait_for(|| bus.notifier.shown.lock().unwrap().contains(&1)).await;
+        let ctxt = SignalContext::new(&bus.server, PATH).unwrap();
+        FakeServer::action_invoked(&ctxt, 1, SKIP_ACCEPT_ACTION).await.unwrap();
+
+        wait_for(|| bus.app_state.lock().unwrap().skip_accept_requested).await;
+    }
+
+    #[tokio::test]
+    async fn dodge_countdown_is_announced_once() {
+        let Some(bus) = bus().await else { return };
+
+        bus.notifier.dodge_pending(20);
+        bus.notifier.dodge_pending(19);
+        wait_for(|| !bus.received.lock().unwrap().is_empty()).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let received = bus.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].summary, "Dodging in 20s");
+        assert_eq!(received[0].actions, ["cancel-dodge", "Cancel dodge"]);
+    }
+
+    #[tokio::test]
+    async fn events_not_configured_are_skipped() {
+        let Some(bus) = bus().await else { return };
+
+        bus.notifier.notify(&payload(GameEvent::GameStarted));
+        bus.notifier.notify(&payload(GameEvent::BanTurn));
+        wait_for(|| !bus.received.lock().unwrap().is_empty()).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let received = bus.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].summary, "Your turn to ban");
+    }
+}
@@ -0,0 +1,38 @@
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const INDEX_HTML: &str = include_str!("../assets/overlay/index.html");
+const OVERLAY_JS: &str = include_str!("../assets/overlay/overlay.js");
+const DEFAULT_CSS: &str = include_str!("../assets/overlay/default.css");
+
+/// The OBS browser-source overlay. The page itself holds no data, so these
+/// routes are public; it reads the token from its own URL for the event stream.
+pub fn router(theme: Option<PathBuf>) -> Router {
+    Router::new()
+        .route("/overlay", get(|| async { asset("text/html", INDEX_HTML) }))
+        .route("/overlay/overlay.js", get(|| async { asset("text/javascript", OVERLAY_JS) }))
+        .route("/overlay/default.css", get(|| async { asset("text/css", DEFAULT_CSS) }))
+        .route("/overlay/theme.css", get(theme_css))
+        .with_state(Arc::new(theme))
+}
+
+fn asset(content_type: &'static str, body: &'static str) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, content_type)], body)
+}
+
+/// Read on every request so theme edits show up on the next browser-source refresh
+async fn theme_css(State(theme): State<Arc<Option<PathBuf>>>) -> impl IntoResponse {
+    let css = match theme.as_ref() {
+        Some(path) => match tokio::fs::read_to_string(path).await {
+            Ok(css) => css,
+            Err(_) => return StatusCode::NOT_FOUND.into_response(),
+        },
+        None => String::new(),
+    };
+    ([(header::CONTENT_TYPE, "text/css")], css).into_response()
+}
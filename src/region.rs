@@ -1,10 +1,112 @@
 use serde::{Deserialize, Serialize};
+use crate::lcu_client::LCUClient;
+use tracing::warn;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// Some builds leave fields out or add new ones, so everything defaults and
+/// unknown fields are ignored
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct RegionInfo {
     pub locale: String,
     pub region: String,
     pub web_language: String,
     pub web_region: String,
-}
\ No newline at end of file
+}
+
+impl RegionInfo {
+    /// Region code used by the multi-search sites
+    pub fn multi_region(&self) -> &str {
+        let web_region = if self.web_region.is_empty() {
+            &self.region
+        } else {
+            &self.web_region
+        };
+        if web_region.eq_ignore_ascii_case("SG2") {
+            "SG"
+        } else {
+            web_region
+        }
+    }
+
+    /// Short lowercase region used by TFT sites (e.g. `na`, `euw`, `sg`)
+    pub fn tft_region(&self) -> String {
+        self.multi_region()
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .to_lowercase()
+    }
+
+    /// Riot platform id (e.g. `na1`, `euw1`, `kr`)
+    pub fn platform_id(&self) -> String {
+        let platform = match self.region.to_uppercase().as_str() {
+            "NA" => "na1",
+            "EUW" => "euw1",
+            "EUNE" => "eun1",
+            "KR" => "kr",
+            "JP" => "jp1",
+            "BR" => "br1",
+            "LAN" => "la1",
+            "LAS" => "la2",
+            "OCE" => "oc1",
+            "TR" => "tr1",
+            "RU" => "ru",
+            _ => return self.region.to_lowercase(),
+        };
+        platform.to_string()
+    }
+}
+
+pub async fn get_region_info(app_client: &LCUClient) -> Option<RegionInfo> {
+    match app_client.get("/riotclient/region-locale").await {
+        Ok(response) => match response.json().await {
+            Ok(info) => Some(info),
+            Err(e) => {
+                warn!("Failed to parse region info: {:?}", e);
+                None
+            }
+        },
+        Err(_) => {
+            warn!("Failed to get region info");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-written after the shape of client responses, one directory per region;
+    /// not yet checked against real captures
+    const REGION_LOCALE: [(&str, &str); 4] = [
+        ("na", include_str!("../tests/fixtures/lcu/na/riotclient_region-locale.json")),
+        ("euw", include_str!("../tests/fixtures/lcu/euw/riotclient_region-locale.json")),
+        ("kr", include_str!("../tests/fixtures/lcu/kr/riotclient_region-locale.json")),
+        ("sg2", include_str!("../tests/fixtures/lcu/sg2/riotclient_region-locale.json")),
+    ];
+
+    #[test]
+    fn region_codes_in_every_region() {
+        let expected = [
+            ("na", "NA", "na", "na1"),
+            ("euw", "EUW", "euw", "euw1"),
+            ("kr", "KR", "kr", "kr"),
+            // No web region in this payload, so the region is used
+            ("sg2", "SG", "sg", "sg2"),
+        ];
+        for ((region, payload), (_, multi, tft, platform)) in REGION_LOCALE.iter().zip(expected) {
+            let info: RegionInfo =
+                serde_json::from_str(payload).unwrap_or_else(|e| panic!("{}: {}", region, e));
+            assert_eq!(info.multi_region(), multi, "{}", region);
+            assert_eq!(info.tft_region(), tft, "{}", region);
+            assert_eq!(info.platform_id(), platform, "{}", region);
+        }
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        // sg2 has `isPBE`, which the model doesn't know
+        let info: RegionInfo = serde_json::from_str(REGION_LOCALE[3].1).unwrap();
+        assert_eq!(info.web_region, "");
+        assert!(serde_json::to_value(&info).unwrap().get("isPBE").is_none());
+    }
+}
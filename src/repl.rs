@@ -0,0 +1,536 @@
+use crate::lcu_client::{self, LCUClient, LCUClientInfo, LcuError};
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::{Method, StatusCode};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, ExternalPrinter, Helper};
+use serde_json::Value;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+const HISTORY_DIR: &str = "league-reveal-console";
+const HISTORY_FILE: &str = "repl-history.txt";
+const PROMPT: &str = "lcu> ";
+const METHODS: [&str; 5] = ["GET", "POST", "PUT", "PATCH", "DELETE"];
+const COMMANDS: [&str; 5] = ["subscribe", "unsubscribe", "help", "exit", "quit"];
+// Every LCU event; narrower ones append the endpoint path
+const ALL_EVENTS: &str = "OnJsonApiEvent";
+
+// WAMP 1.0 message types used by the client's WebSocket
+const WAMP_SUBSCRIBE: u8 = 5;
+const WAMP_UNSUBSCRIBE: u8 = 6;
+const WAMP_EVENT: u8 = 8;
+
+const HELP: &str = "  GET /path                  GET an endpoint (the method can be left out)
+  POST /path {\"json\": 1}     POST, PUT, PATCH or DELETE with an optional JSON body
+  subscribe [/path | event]  Print live WebSocket events, all of them without an argument
+  unsubscribe [/path | event]
+  help                       Show this help
+  exit                       Leave (or Ctrl-D)
+Tab completes endpoint paths from the client's /help.";
+
+/// Endpoint paths and event names for tab completion
+struct ReplHelper {
+    paths: Vec<String>,
+    events: Vec<String>,
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(' ').map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        let first_word = start == 0;
+        let subscribing = line.starts_with("subscribe ") || line.starts_with("unsubscribe ");
+
+        let candidates: Box<dyn Iterator<Item = &str>> = if first_word {
+            Box::new(
+                METHODS
+                    .iter()
+                    .chain(COMMANDS.iter())
+                    .copied()
+                    .chain(self.paths.iter().map(String::as_str)),
+            )
+        } else if subscribing && !word.starts_with('/') {
+            Box::new(self.events.iter().map(String::as_str))
+        } else {
+            Box::new(self.paths.iter().map(String::as_str))
+        };
+        let matches = candidates
+            .filter(|candidate| {
+                candidate.starts_with(word)
+                    || (first_word && candidate.starts_with(&word.to_uppercase()))
+            })
+            .map(|candidate| Pair {
+                display: candidate.to_string(),
+                replacement: candidate.to_string(),
+            })
+            .collect();
+        Ok((start, matches))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+struct StdoutPrinter;
+
+impl ExternalPrinter for StdoutPrinter {
+    fn print(&mut self, msg: String) -> rustyline::Result<()> {
+        println!("{}", msg);
+        Ok(())
+    }
+}
+
+/// `repl` subcommand
+pub async fn run() -> Result<(), String> {
+    let info = lcu_client::find_league_client()
+        .await
+        .ok_or("League Client is not running")?;
+    let client = LCUClient::new(&info)
+        .await
+        .map_err(|e| format!("Failed to create client: {}", e))?;
+
+    let (paths, events) = load_help(&client).await;
+    println!(
+        "Connected on port {}, {} endpoints known. Type help for commands.",
+        info.port,
+        paths.len()
+    );
+
+    let mut editor: Editor<ReplHelper, FileHistory> =
+        Editor::new().map_err(|e| format!("Failed to start the REPL: {}", e))?;
+    editor.set_helper(Some(ReplHelper { paths, events }));
+    let history = history_path();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+    // Without a terminal (e.g. commands piped in) events are simply printed
+    let printer: Box<dyn ExternalPrinter + Send> = match editor.create_external_printer() {
+        Ok(printer) => Box::new(printer),
+        Err(_) => Box::new(StdoutPrinter),
+    };
+    let mut subscriptions = Subscriptions::new(info, printer);
+
+    loop {
+        // Reading a line blocks, so it runs off the runtime that delivers events
+        let (returned, line) = tokio::task::spawn_blocking(move || {
+            let line = editor.readline(PROMPT);
+            (editor, line)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        editor = returned;
+
+        let line = match line {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.to_string()),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        match command {
+            "exit" | "quit" => break,
+            "help" => println!("{}", HELP),
+            "subscribe" => subscriptions.send(WAMP_SUBSCRIBE, &event_name(rest)),
+            "unsubscribe" => subscriptions.send(WAMP_UNSUBSCRIBE, &event_name(rest)),
+            _ if command.starts_with('/') => call(&client, Method::GET, command, rest).await,
+            _ => match Method::from_bytes(command.to_uppercase().as_bytes()) {
+                Ok(method) if METHODS.contains(&method.as_str()) => {
+                    let (path, body) = rest.split_once(' ').unwrap_or((rest, ""));
+                    call(&client, method, path, body.trim()).await
+                }
+                _ => println!("Unknown command {:?}, type help for commands", command),
+            },
+        }
+    }
+
+    if let Some(history) = &history {
+        if let Some(dir) = history.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(e) = editor.save_history(history) {
+            eprintln!("Failed to save history to {}: {}", history.display(), e);
+        }
+    }
+    Ok(())
+}
+
+/// `$XDG_DATA_HOME/league-reveal-console/repl-history.txt`
+fn history_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(HISTORY_DIR).join(HISTORY_FILE))
+}
+
+/// Endpoint paths and event names from `/help`. Empty when the client doesn't
+/// serve it, which only costs tab completion.
+async fn load_help(client: &LCUClient) -> (Vec<String>, Vec<String>) {
+    let help: Value = match client.get("/help?format=Full").await {
+        Ok(response) => response.json().await.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("No endpoint completion, /help failed: {}", e);
+            return (Vec::new(), Vec::new());
+        }
+    };
+
+    let mut paths: Vec<String> = help["functions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|function| function["url"].as_str())
+        .filter(|url| url.starts_with('/'))
+        .map(|url| url.to_string())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    let mut events: Vec<String> = help["events"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|event| event["name"].as_str())
+        .map(|name| name.to_string())
+        .collect();
+    events.sort();
+    (paths, events)
+}
+
+async fn call(client: &LCUClient, method: Method, path: &str, body: &str) {
+    if path.is_empty() {
+        println!("Missing endpoint path");
+        return;
+    }
+    let body = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_str(body) {
+            Ok(body) => Some(body),
+            Err(e) => {
+                println!("Invalid JSON body: {}", e);
+                return;
+            }
+        }
+    };
+
+    let (status, text) = match client.request(method, path, body).await {
+        Ok(response) => (response.status(), response.text().await.unwrap_or_default()),
+        Err(LcuError::Status { status, body, .. }) => (status, body),
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    println!("{}", format_response(status, &text));
+}
+
+/// The body, pretty-printed when it is JSON. Error responses start with their
+/// status, since their body alone can look like a normal answer.
+fn format_response(status: StatusCode, text: &str) -> String {
+    let body = match serde_json::from_str::<Value>(text) {
+        Ok(value) => pretty(&value),
+        Err(_) => text.to_string(),
+    };
+    if body.is_empty() {
+        status.to_string()
+    } else if status.is_success() {
+        body
+    } else {
+        format!("{}\n{}", status, body)
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// `/lol-gameflow/v1/session` becomes `OnJsonApiEvent_lol-gameflow_v1_session`
+fn event_name(argument: &str) -> String {
+    if argument.is_empty() {
+        ALL_EVENTS.to_string()
+    } else if argument.starts_with('/') {
+        format!("{}{}", ALL_EVENTS, argument.replace('/', "_"))
+    } else {
+        argument.to_string()
+    }
+}
+
+/// The client's WebSocket, opened on the first subscription. Events are printed
+/// above the prompt.
+struct Subscriptions {
+    info: LCUClientInfo,
+    printer: Option<Box<dyn ExternalPrinter + Send>>,
+    messages: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl Subscriptions {
+    fn new(info: LCUClientInfo, printer: Box<dyn ExternalPrinter + Send>) -> Self {
+        Subscriptions {
+            info,
+            printer: Some(printer),
+            messages: None,
+        }
+    }
+
+    fn send(&mut self, message_type: u8, event: &str) {
+        let message = serde_json::json!([message_type, event]).to_string();
+        if let Some(messages) = &self.messages {
+            if messages.send(message.clone()).is_ok() {
+                return;
+            }
+        }
+        // Not connected yet, or the connection dropped and the printer went with it
+        let printer = match self.printer.take() {
+            Some(printer) => printer,
+            None => {
+                println!("Event connection closed, restart the REPL to subscribe again");
+                return;
+            }
+        };
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let _ = sender.send(message);
+        self.messages = Some(sender);
+        tokio::spawn(stream_events(self.info.clone(), receiver, printer));
+    }
+}
+
+async fn stream_events(
+    info: LCUClientInfo,
+    mut messages: mpsc::UnboundedReceiver<String>,
+    mut printer: Box<dyn ExternalPrinter + Send>,
+) {
+    let mut socket = match connect_websocket(&info).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            let _ = printer.print(format!("Failed to open the event WebSocket: {}", e));
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            message = messages.recv() => {
+                let Some(message) = message else { return };
+                if let Err(e) = socket.send(Message::Text(message)).await {
+                    let _ = printer.print(format!("Event WebSocket failed: {}", e));
+                    return;
+                }
+            }
+            frame = socket.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        let _ = printer.print(format!("Event WebSocket failed: {}", e));
+                        return;
+                    }
+                    None => {
+                        let _ = printer.print("Event WebSocket closed".to_string());
+                        return;
+                    }
+                };
+                if let Some(event) = format_event(&text) {
+                    let _ = printer.print(event);
+                }
+            }
+        }
+    }
+}
+
+type WebSocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn connect_websocket(info: &LCUClientInfo) -> Result<WebSocket, Box<dyn std::error::Error>> {
+    let scheme = if info.protocol == "http" { "ws" } else { "wss" };
+    let mut request = format!("{}://127.0.0.1:{}/", scheme, info.port).into_client_request()?;
+    let credentials =
+        base64::engine::general_purpose::STANDARD.encode(format!("riot:{}", info.password));
+    request
+        .headers_mut()
+        .insert("Authorization", format!("Basic {}", credentials).parse()?);
+    // The client's certificate is self-signed, same as for the HTTP API
+    let tls = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(true)
+        .build()?;
+    let (socket, _) = tokio_tungstenite::connect_async_tls_with_config(
+        request,
+        None,
+        false,
+        Some(Connector::NativeTls(tls)),
+    )
+    .await?;
+    Ok(socket)
+}
+
+/// `[8, "OnJsonApiEvent", {"eventType": "Update", "uri": "/...", "data": ...}]`
+fn format_event(text: &str) -> Option<String> {
+    let message: Value = serde_json::from_str(text).ok()?;
+    if message[0].as_u64() != Some(WAMP_EVENT as u64) {
+        return None;
+    }
+    let payload = &message[2];
+    Some(format!(
+        "[{}] {} {}\n{}",
+        message[1].as_str().unwrap_or_default(),
+        payload["eventType"].as_str().unwrap_or_default(),
+        payload["uri"].as_str().unwrap_or_default(),
+        pretty(&payload["data"])
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustyline::history::DefaultHistory;
+
+    fn helper() -> ReplHelper {
+        ReplHelper {
+            paths: vec![
+                "/lol-gameflow/v1/gameflow-phase".to_string(),
+                "/lol-gameflow/v1/session".to_string(),
+                "/lol-summoner/v1/current-summoner".to_string(),
+            ],
+            events: vec![
+                "OnJsonApiEvent".to_string(),
+                "OnJsonApiEvent_lol-gameflow_v1_session".to_string(),
+                "OnServiceProxyAsyncEvent".to_string(),
+            ],
+        }
+    }
+
+    /// Start of the replaced word and the candidates for a cursor at the end of `line`
+    fn complete(line: &str) -> (usize, Vec<String>) {
+        let history = DefaultHistory::new();
+        let (start, pairs) = helper()
+            .complete(line, line.len(), &Context::new(&history))
+            .unwrap();
+        (
+            start,
+            pairs.into_iter().map(|pair| pair.replacement).collect(),
+        )
+    }
+
+    #[test]
+    fn event_names_from_paths() {
+        assert_eq!(event_name(""), "OnJsonApiEvent");
+        assert_eq!(
+            event_name("/lol-gameflow/v1/session"),
+            "OnJsonApiEvent_lol-gameflow_v1_session"
+        );
+        assert_eq!(
+            event_name("OnServiceProxyAsyncEvent"),
+            "OnServiceProxyAsyncEvent"
+        );
+    }
+
+    #[test]
+    fn formats_wamp_events() {
+        let text = r#"[8, "OnJsonApiEvent", {"eventType": "Update", "uri": "/lol-gameflow/v1/gameflow-phase", "data": "ReadyCheck"}]"#;
+        assert_eq!(
+            format_event(text).unwrap(),
+            "[OnJsonApiEvent] Update /lol-gameflow/v1/gameflow-phase\n\"ReadyCheck\""
+        );
+
+        let nested = r#"[8, "OnJsonApiEvent_lol-lobby_v2_lobby", {"eventType": "Delete", "uri": "/lol-lobby/v2/lobby", "data": {"partyId": "p"}}]"#;
+        assert_eq!(
+            format_event(nested).unwrap(),
+            "[OnJsonApiEvent_lol-lobby_v2_lobby] Delete /lol-lobby/v2/lobby\n{\n  \"partyId\": \"p\"\n}"
+        );
+    }
+
+    #[test]
+    fn ignores_other_wamp_messages() {
+        // Welcome, a subscription echo and garbage
+        assert_eq!(format_event(r#"[0, "session", 1, "server"]"#), None);
+        assert_eq!(format_event(r#"[5, "OnJsonApiEvent"]"#), None);
+        assert_eq!(format_event("not json"), None);
+    }
+
+    #[test]
+    fn completes_methods_commands_and_paths_first() {
+        assert_eq!(
+            complete("P"),
+            (
+                0,
+                vec!["POST".to_string(), "PUT".to_string(), "PATCH".to_string()]
+            )
+        );
+        // Methods complete case-insensitively
+        assert_eq!(complete("ge").1, vec!["GET".to_string()]);
+        assert_eq!(complete("sub").1, vec!["subscribe".to_string()]);
+        assert_eq!(
+            complete("/lol-game").1,
+            vec![
+                "/lol-gameflow/v1/gameflow-phase".to_string(),
+                "/lol-gameflow/v1/session".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn completes_paths_after_a_method() {
+        assert_eq!(
+            complete("GET /lol-sum"),
+            (4, vec!["/lol-summoner/v1/current-summoner".to_string()])
+        );
+        assert!(complete("GET /nothing").1.is_empty());
+    }
+
+    #[test]
+    fn completes_events_or_paths_when_subscribing() {
+        assert_eq!(
+            complete("subscribe OnJsonApiEvent_"),
+            (
+                10,
+                vec!["OnJsonApiEvent_lol-gameflow_v1_session".to_string()]
+            )
+        );
+        assert_eq!(
+            complete("unsubscribe /lol-gameflow/v1/s"),
+            (12, vec!["/lol-gameflow/v1/session".to_string()])
+        );
+    }
+
+    #[test]
+    fn error_responses_show_their_status() {
+        let body = r#"{"errorCode":"RPC_ERROR","httpStatus":404,"message":"Invalid URI format"}"#;
+        let formatted = format_response(StatusCode::NOT_FOUND, body);
+        assert!(formatted.starts_with("404 Not Found\n{"), "{}", formatted);
+        assert!(formatted.contains("\"message\": \"Invalid URI format\""));
+
+        assert_eq!(format_response(StatusCode::OK, "\"Lobby\""), "\"Lobby\"");
+        assert_eq!(
+            format_response(StatusCode::NO_CONTENT, ""),
+            "204 No Content"
+        );
+        assert_eq!(
+            format_response(StatusCode::BAD_REQUEST, "oops"),
+            "400 Bad Request\noops"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{debug, warn};
+
+const LOCK_FILE: &str = "league-reveal-console.pid";
+const UNIT_NAME: &str = "league-reveal-console.service";
+
+/// Held for as long as the console runs so a second copy can't accept the
+/// same ready checks. The file holds our PID and is left in place on exit:
+/// removing it could let a copy that already opened it and one that creates
+/// a new file both take a lock.
+pub struct InstanceLock {
+    // The lock is released when the file is closed
+    _file: File,
+}
+
+impl InstanceLock {
+    pub fn acquire() -> Result<Self, String> {
+        let path = lock_path().ok_or("No runtime or state directory for the lock file")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+        if file.try_lock().is_err() {
+            let pid = std::fs::read_to_string(&path).unwrap_or_default();
+            return Err(format!(
+                "Another copy is already running (PID {}, lock file {})",
+                pid.trim(),
+                path.display()
+            ));
+        }
+        file.set_len(0)
+            .and_then(|_| write!(file, "{}", std::process::id()))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        debug!("Holding {}", path.display());
+
+        Ok(InstanceLock { _file: file })
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/league-reveal-console.pid`, or the log directory without a runtime dir
+fn lock_path() -> Option<PathBuf> {
+    dirs::runtime_dir()
+        .or_else(crate::logging::log_dir)
+        .map(|dir| dir.join(LOCK_FILE))
+}
+
+/// Listens for SIGINT and SIGTERM from the moment it is called, not from the
+/// first poll; the returned future resolves with the signal's name
+pub fn shutdown_signal() -> impl Future<Output = &'static str> {
+    let signals = (
+        signal(SignalKind::interrupt()),
+        signal(SignalKind::terminate()),
+    );
+    async move {
+        let (mut interrupt, mut terminate) = match signals {
+            (Ok(interrupt), Ok(terminate)) => (interrupt, terminate),
+            _ => {
+                warn!("Failed to listen for shutdown signals");
+                return std::future::pending().await;
+            }
+        };
+        tokio::select! {
+            _ = interrupt.recv() => "SIGINT",
+            _ = terminate.recv() => "SIGTERM",
+        }
+    }
+}
+
+/// Sets the returned flag on every SIGHUP; the poll loop reloads the config when it sees it
+pub fn reload_on_hangup() -> Arc<AtomicBool> {
+    let requested = Arc::new(AtomicBool::new(false));
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!("Failed to listen for SIGHUP, config reload is unavailable: {}", e);
+            return requested;
+        }
+    };
+    let flag = requested.clone();
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    });
+    requested
+}
+
+/// `systemd-unit` subcommand
+pub fn print_systemd_unit() -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to find the current executable: {}", e))?;
+    println!("# Save as ~/.config/systemd/user/{}, then run:", UNIT_NAME);
+    println!("#   systemctl --user daemon-reload && systemctl --user enable --now {}", UNIT_NAME);
+    println!("[Unit]");
+    println!("Description=League Reveal Console");
+    println!("After=graphical-session.target");
+    println!();
+    println!("[Service]");
+    // systemd splits ExecStart on spaces unless quoted
+    println!("ExecStart=\"{}\" --daemon", exe.display());
+    println!("ExecReload=/bin/kill -HUP $MAINPID");
+    println!("Restart=on-failure");
+    println!("RestartSec=5");
+    println!();
+    println!("[Install]");
+    println!("WantedBy=default.target");
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::browser::resolve_executable;
+use crate::config::SoundConfig;
+use crate::events::GameEvent;
+use rand::distributions::{Alphanumeric, DistString};
+use std::f32::consts::PI;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, warn};
+
+const PLAYERS: [&str; 4] = ["pw-play", "paplay", "aplay", "afplay"];
+const SAMPLE_RATE: u32 = 44_100;
+const RUNTIME_DIR: &str = "league-reveal-console";
+
+/// Plays alerts through an external audio player
+pub struct Sound {
+    /// `None` when sound is disabled or no player was found
+    player: Option<PathBuf>,
+    config: SoundConfig,
+}
+
+/// Frequency (Hz) and length (ms) of each tone; 0 Hz is a pause
+type Tones = &'static [(f32, u32)];
+
+const READY_CHECK_BEEP: Tones = &[(880.0, 150), (0.0, 80), (880.0, 150), (0.0, 80), (1175.0, 250)];
+const TURN_BEEP: Tones = &[(660.0, 120), (0.0, 60), (990.0, 180)];
+
+impl Sound {
+    pub fn new(config: &SoundConfig) -> Self {
+        let player = if config.enabled {
+            let player = match &config.player {
+                Some(player) => resolve_executable(player),
+                None => PLAYERS.iter().find_map(|player| resolve_executable(player)),
+            };
+            if player.is_none() {
+                warn!("Sound is enabled but no audio player was found, alerts stay silent");
+            }
+            player
+        } else {
+            None
+        };
+        Sound {
+            player,
+            config: config.clone(),
+        }
+    }
+
+    pub fn play(&self, event: GameEvent) {
+        let player = match &self.player {
+            Some(player) => player,
+            None => return,
+        };
+        let beep = match event {
+            GameEvent::ReadyCheck => READY_CHECK_BEEP,
+            GameEvent::PickTurn | GameEvent::BanTurn => TURN_BEEP,
+            _ => return,
+        };
+        let now = chrono::Local::now().time();
+        if self.config.quiet_hours.as_ref().is_some_and(|quiet| quiet.contains(now)) {
+            debug!("Quiet hours, not playing a sound for {:?}", event);
+            return;
+        }
+
+        let result = match self.config.file(event) {
+            Some(file) => spawn(player, &file, self.config.volume, false),
+            // The beep is generated at the right volume, so the player doesn't need to scale it
+            None => write_beep(event, beep, self.config.volume)
+                .and_then(|file| spawn(player, &file, 1.0, true)),
+        };
+        if let Err(e) = result {
+            warn!("Failed to play sound with {}: {}", player.display(), e);
+        }
+    }
+}
+
+/// Plays `file`, removing it afterwards when it is `temporary`
+fn spawn(player: &Path, file: &Path, volume: f32, temporary: bool) -> std::io::Result<()> {
+    let name = player.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let mut command = Command::new(player);
+    match name {
+        "pw-play" => command.arg(format!("--volume={}", volume)),
+        "paplay" => command.arg(format!("--volume={}", (volume * 65536.0) as u32)),
+        "afplay" => command.arg("-v").arg(volume.to_string()),
+        "aplay" => command.arg("-q"),
+        _ => &mut command,
+    };
+
+    let mut child = command.arg(file).spawn()?;
+    // Reap the player in the background like the browser launcher does
+    let file = file.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = child.wait();
+        if temporary {
+            let _ = std::fs::remove_file(file);
+        }
+    });
+    Ok(())
+}
+
+/// Writes the beep for `event` to a new WAV file, to remove once played. Every
+/// playback gets its own file, so a second alert can't rewrite one that a
+/// player is still reading.
+fn write_beep(event: GameEvent, tones: Tones, volume: f32) -> std::io::Result<PathBuf> {
+    let wav = beep_wav(tones, volume);
+    let dir = match dirs::runtime_dir() {
+        Some(dir) => {
+            let dir = dir.join(RUNTIME_DIR);
+            std::fs::create_dir_all(&dir)?;
+            dir
+        }
+        None => std::env::temp_dir(),
+    };
+
+    // Others can write to the temp dir, so always create a new file under a
+    // random name instead of writing through whatever (or wherever) a name points to
+    let name = format!(
+        "league-reveal-console-{:?}-{}.wav",
+        event,
+        Alphanumeric.sample_string(&mut rand::thread_rng(), 12)
+    );
+    let path = dir.join(name);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(&wav)?;
+    Ok(path)
+}
+
+/// 16-bit mono PCM
+fn beep_wav(tones: Tones, volume: f32) -> Vec<u8> {
+    let mut samples = Vec::new();
+    for &(frequency, ms) in tones {
+        let count = SAMPLE_RATE * ms / 1000;
+        for i in 0..count {
+            // Short fade in and out so the tones don't click
+            let fade = (i.min(count - i) as f32 / (SAMPLE_RATE as f32 * 0.005)).min(1.0);
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let sample = (2.0 * PI * frequency * t).sin() * volume * fade * i16::MAX as f32 * 0.6;
+            samples.push(sample as i16);
+        }
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(wav: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(wav[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(wav: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(wav[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn beep_is_a_16_bit_mono_wav() {
+        let wav = beep_wav(READY_CHECK_BEEP, 1.0);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 4) as usize, wav.len() - 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(&wav, 16), 16);
+        assert_eq!(u16_at(&wav, 20), 1, "PCM");
+        assert_eq!(u16_at(&wav, 22), 1, "mono");
+        assert_eq!(u32_at(&wav, 24), SAMPLE_RATE);
+        assert_eq!(u32_at(&wav, 28), SAMPLE_RATE * 2, "byte rate");
+        assert_eq!(u16_at(&wav, 32), 2, "block align");
+        assert_eq!(u16_at(&wav, 34), 16, "bits per sample");
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(&wav, 40) as usize, wav.len() - 44);
+    }
+
+    #[test]
+    fn beep_length_follows_the_tones() {
+        // 150 + 80 + 150 + 80 + 250 ms
+        let samples = (SAMPLE_RATE * 710 / 1000) as usize;
+        assert_eq!(beep_wav(READY_CHECK_BEEP, 1.0).len(), 44 + samples * 2);
+        assert_eq!(beep_wav(&[], 1.0).len(), 44);
+    }
+
+    #[test]
+    fn volume_scales_the_samples() {
+        let peak = |volume| {
+            beep_wav(TURN_BEEP, volume)[44..]
+                .chunks(2)
+                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]).unsigned_abs())
+                .max()
+                .unwrap()
+        };
+        assert_eq!(peak(0.0), 0);
+        assert!(peak(0.5) < peak(1.0));
+        assert!(peak(1.0) <= (i16::MAX as f32 * 0.6) as u16 + 1);
+    }
+
+    #[test]
+    fn every_beep_gets_its_own_file() {
+        let first = write_beep(GameEvent::PickTurn, TURN_BEEP, 1.0).unwrap();
+        let second = write_beep(GameEvent::PickTurn, TURN_BEEP, 1.0).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), beep_wav(TURN_BEEP, 1.0));
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+}
@@ -0,0 +1,220 @@
+use crate::lcu_client::LCUClientInfo;
+use crate::live_view::ChampSelectSnapshot;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+const PHASE_HISTORY_LEN: usize = 20;
+const ACTIONS_LEN: usize = 50;
+const LOG_LEN: usize = 200;
+const EVENTS_LEN: usize = 64;
+
+/// App state shared between the polling loop and the UI
+pub type SharedState = Arc<Mutex<AppState>>;
+
+pub struct LCUState {
+    pub connected: bool,
+    pub data: Option<LCUClientInfo>,
+}
+
+pub struct DodgeState {
+    /// When the last dodge was sent (ms since epoch)
+    pub last_dodge: Option<u64>,
+    /// When the dodge was armed (ms since epoch), `None` when disarmed
+    pub enabled: Option<u64>,
+}
+
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub struct PhaseChange {
+    pub phase: String,
+    pub at: u64,
+}
+
+/// Pushed to local API subscribers whenever the state changes
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StateEvent {
+    Snapshot { state: StateSnapshot },
+    Connection { connected: bool },
+    Phase { phase: String },
+    ChampSelect { champ_select: Option<ChampSelectSnapshot> },
+    Links { links: Vec<String> },
+    Automations { paused: bool, auto_accept_enabled: bool },
+    Action { action: String },
+}
+
+/// Everything the local API exposes, in one response
+#[derive(Debug, Clone, Serialize)]
+pub struct StateSnapshot {
+    pub connected: bool,
+    pub phase: Option<String>,
+    pub champ_select: Option<ChampSelectSnapshot>,
+    pub last_links: Vec<String>,
+    pub automations_paused: bool,
+    pub auto_accept_enabled: bool,
+}
+
+pub struct AppState {
+    pub lcu: LCUState,
+    pub phase_history: VecDeque<PhaseChange>,
+    pub champ_select_board: Vec<String>,
+    pub champ_select: Option<ChampSelectSnapshot>,
+    pub actions: VecDeque<String>,
+    pub log: VecDeque<String>,
+    /// Runtime switch on top of the configured auto-accept
+    pub auto_accept_enabled: bool,
+    /// Stops auto-accept and link opening until resumed
+    pub automations_paused: bool,
+    pub dodge: DodgeState,
+    pub last_links: Vec<String>,
+    pub reopen_requested: bool,
+    pub multi_search_requested: bool,
+    /// Don't auto-accept the current ready check; it is left for the user
+    pub skip_accept_requested: bool,
+    events: broadcast::Sender<StateEvent>,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        AppState {
+            lcu: LCUState {
+                connected: false,
+                data: None,
+            },
+            phase_history: VecDeque::new(),
+            champ_select_board: Vec::new(),
+            champ_select: None,
+            actions: VecDeque::new(),
+            log: VecDeque::new(),
+            auto_accept_enabled: true,
+            automations_paused: false,
+            dodge: DodgeState {
+                last_dodge: None,
+                enabled: None,
+            },
+            last_links: Vec::new(),
+            reopen_requested: false,
+            multi_search_requested: false,
+            skip_accept_requested: false,
+            events: broadcast::channel(EVENTS_LEN).0,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StateEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&self, event: StateEvent) {
+        // Nobody listening is fine
+        let _ = self.events.send(event);
+    }
+
+    pub fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            connected: self.lcu.connected,
+            phase: self.current_phase().map(|phase| phase.to_string()),
+            champ_select: self.champ_select.clone(),
+            last_links: self.last_links.clone(),
+            automations_paused: self.automations_paused,
+            auto_accept_enabled: self.auto_accept_enabled,
+        }
+    }
+
+    pub fn current_phase(&self) -> Option<&str> {
+        self.phase_history.back().map(|change| change.phase.as_str())
+    }
+
+    pub fn set_connected(&mut self, connected: bool, data: Option<LCUClientInfo>) {
+        if data.is_some() {
+            self.lcu.data = data;
+        }
+        if self.lcu.connected != connected {
+            self.lcu.connected = connected;
+            self.emit(StateEvent::Connection { connected });
+        }
+    }
+
+    pub fn record_phase(&mut self, phase: &str) {
+        push_bounded(
+            &mut self.phase_history,
+            PhaseChange {
+                phase: phase.to_string(),
+                at: now_ms(),
+            },
+            PHASE_HISTORY_LEN,
+        );
+        self.emit(StateEvent::Phase {
+            phase: phase.to_string(),
+        });
+    }
+
+    pub fn set_champ_select(&mut self, board: Vec<String>, champ_select: Option<ChampSelectSnapshot>) {
+        self.champ_select_board = board;
+        if self.champ_select != champ_select {
+            self.champ_select = champ_select.clone();
+            self.emit(StateEvent::ChampSelect { champ_select });
+        }
+    }
+
+    pub fn record_action(&mut self, action: String) {
+        push_bounded(&mut self.actions, action.clone(), ACTIONS_LEN);
+        self.emit(StateEvent::Action { action });
+    }
+
+    pub fn record_log(&mut self, line: String) {
+        push_bounded(&mut self.log, line, LOG_LEN);
+    }
+
+    pub fn record_links(&mut self, links: Vec<String>) {
+        if !links.is_empty() {
+            self.last_links = links.clone();
+            self.emit(StateEvent::Links { links });
+        }
+    }
+
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn toggle_auto_accept(&mut self) {
+        self.auto_accept_enabled = !self.auto_accept_enabled;
+        self.emit_automations();
+    }
+
+    pub fn set_automations_paused(&mut self, paused: bool) {
+        self.automations_paused = paused;
+        self.emit_automations();
+    }
+
+    fn emit_automations(&self) {
+        self.emit(StateEvent::Automations {
+            paused: self.automations_paused,
+            auto_accept_enabled: self.auto_accept_enabled,
+        });
+    }
+
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub fn toggle_dodge(&mut self) {
+        self.dodge.enabled = match self.dodge.enabled {
+            Some(_) => None,
+            None => Some(now_ms()),
+        };
+    }
+}
+
+pub fn new_shared_state() -> SharedState {
+    Arc::new(Mutex::new(AppState::new()))
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, limit: usize) {
+    if queue.len() == limit {
+        queue.pop_front();
+    }
+    queue.push_back(item);
+}
@@ -1,29 +1,43 @@
 use serde::{Deserialize, Serialize};
 use crate::lcu_client::LCUClient;
+use tracing::warn;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+/// The logged-in account. Only the Riot ID, puuid and ids are relied on; the
+/// rest differs between client builds and regions, so everything defaults and
+/// fields this model doesn't know are ignored.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct Summoner {
     pub account_id: i64,
-    pub display_name: String,
     pub game_name: String,
-    pub internal_name: String,
-    pub name_change_flag: bool,
-    pub percent_complete_for_next_level: i64,
-    pub privacy: String,
+    pub tag_line: String,
     pub profile_icon_id: i64,
     pub puuid: String,
-    pub reroll_points: RerollPoints,
     pub summoner_id: i64,
     pub summoner_level: i64,
-    pub tag_line: String,
-    pub unnamed: bool,
-    pub xp_since_last_level: i64,
-    pub xp_until_next_level: i64,
+    /// Legacy summoner name, gone on newer clients; use the Riot ID instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_change_flag: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent_complete_for_next_level: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reroll_points: Option<RerollPoints>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unnamed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xp_since_last_level: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xp_until_next_level: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct RerollPoints {
     pub current_points: i64,
     pub max_rolls: i64,
@@ -32,12 +46,129 @@ pub struct RerollPoints {
     pub points_to_reroll: i64,
 }
 
-pub async fn get_current_summoner(remoting_client: &LCUClient) -> Summoner {
+impl Summoner {
+    /// `game_name#tag_line`, or `None` for an account without a Riot ID yet
+    pub fn riot_id(&self) -> Option<String> {
+        if self.game_name.is_empty() || self.tag_line.is_empty() {
+            return None;
+        }
+        Some(format!("{}#{}", self.game_name, self.tag_line))
+    }
+}
+
+/// `None` while the client is still logging in (the endpoint answers 404) or
+/// when the response doesn't parse or has no puuid
+pub async fn get_current_summoner(remoting_client: &LCUClient) -> Option<Summoner> {
+    match remoting_client.get("/lol-summoner/v1/current-summoner").await {
+        Ok(response) => match response.json().await {
+            Ok(summoner) => with_puuid(summoner),
+            Err(e) => {
+                warn!("Failed to parse current summoner: {:?}", e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("Failed to get current summoner: {}", e);
+            None
+        }
+    }
+}
+
+/// Every field defaults, so any JSON object parses; without a puuid it isn't an
+/// account and the journal and own-player lookups can't use it
+fn with_puuid(summoner: Summoner) -> Option<Summoner> {
+    if summoner.puuid.is_empty() {
+        warn!("Current summoner has no puuid, ignoring it");
+        return None;
+    }
+    Some(summoner)
+}
+
+/// Looks up a player's Riot ID (game name, tag line) by puuid
+pub async fn get_riot_id(remoting_client: &LCUClient, puuid: &str) -> Option<(String, String)> {
     let response = remoting_client
-        .get("/lol-summoner/v1/current-summoner")
+        .get(&format!("/lol-summoner/v2/summoners/puuid/{}", puuid))
         .await
-        .unwrap();
-    
-    let summoner: Summoner = response.json().await.unwrap();
-    summoner
-}
\ No newline at end of file
+        .ok()?;
+    let summoner: Summoner = response.json().await.ok()?;
+
+    if summoner.game_name.is_empty() || summoner.tag_line.is_empty() {
+        return None;
+    }
+    Some((summoner.game_name, summoner.tag_line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-written after the shape of client responses, one directory per
+    /// region, with made-up account data; not yet checked against real captures
+    const CURRENT_SUMMONER: [(&str, &str); 4] = [
+        ("na", include_str!("../tests/fixtures/lcu/na/lol-summoner_v1_current-summoner.json")),
+        ("euw", include_str!("../tests/fixtures/lcu/euw/lol-summoner_v1_current-summoner.json")),
+        ("kr", include_str!("../tests/fixtures/lcu/kr/lol-summoner_v1_current-summoner.json")),
+        ("sg2", include_str!("../tests/fixtures/lcu/sg2/lol-summoner_v1_current-summoner.json")),
+    ];
+
+    fn parse(region: &str) -> Summoner {
+        let (_, payload) = CURRENT_SUMMONER
+            .iter()
+            .find(|(name, _)| *name == region)
+            .unwrap();
+        serde_json::from_str(payload).unwrap_or_else(|e| panic!("{}: {}", region, e))
+    }
+
+    #[test]
+    fn current_summoner_parses_in_every_region() {
+        for (region, _) in CURRENT_SUMMONER {
+            let summoner = parse(region);
+            assert!(!summoner.puuid.is_empty(), "{}", region);
+            assert!(summoner.summoner_level > 0, "{}", region);
+        }
+    }
+
+    #[test]
+    fn riot_id_is_game_name_and_tag_line() {
+        assert_eq!(parse("na").riot_id().as_deref(), Some("Lane Warden#NA1"));
+        assert_eq!(parse("euw").riot_id().as_deref(), Some("Quiet River#EUW"));
+        assert_eq!(parse("kr").riot_id().as_deref(), Some("조용한강#KR1"));
+        assert_eq!(parse("sg2").riot_id().as_deref(), Some("Morning Tide#0421"));
+        assert_eq!(Summoner::default().riot_id(), None);
+    }
+
+    #[test]
+    fn legacy_fields_are_optional() {
+        let na = parse("na");
+        assert_eq!(na.display_name.as_deref(), Some("Lane Warden"));
+        assert_eq!(na.internal_name.as_deref(), Some("LaneWarden"));
+        assert_eq!(na.reroll_points.map(|points| points.current_points), Some(500));
+
+        let euw = parse("euw");
+        assert_eq!(euw.display_name, None);
+        assert_eq!(euw.internal_name, None);
+
+        let kr = parse("kr");
+        assert_eq!(kr.privacy, None);
+        assert!(kr.reroll_points.is_none());
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored() {
+        // kr has `isSelf`, which the model doesn't know
+        let kr = parse("kr");
+
+        let value = serde_json::to_value(&kr).unwrap();
+        assert!(value.get("isSelf").is_none());
+        assert_eq!(value["gameName"], "조용한강");
+        // Missing legacy fields stay missing instead of turning into nulls
+        assert!(value.get("displayName").is_none());
+    }
+
+    #[test]
+    fn summoner_without_puuid_is_rejected() {
+        let empty: Summoner = serde_json::from_str("{}").unwrap();
+        assert!(with_puuid(empty).is_none());
+        assert!(with_puuid(parse("euw")).is_some());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::state::{now_ms, AppState, SharedState};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{error, warn};
+
+const TICK: Duration = Duration::from_millis(250);
+const HELP: &str = " a: toggle auto-accept   d: arm/disarm dodge   o: reopen multi-search   q: quit";
+
+/// The running dashboard. Its thread is the one that restores the terminal,
+/// so every way of shutting down goes through `stop`.
+pub struct Tui {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Tui {
+    /// Ends the dashboard and waits until the terminal is restored, so the
+    /// shell isn't left in raw mode on the alternate screen
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+/// Starts the dashboard on its own thread; quitting it sends on `quit` so the
+/// app shuts down like on SIGINT. Returns `None` when stdout is not a
+/// terminal, in which case the app keeps printing plain console lines.
+pub fn start(state: SharedState, quit: oneshot::Sender<()>) -> Option<Tui> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let mut terminal = match ratatui::try_init() {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            warn!("Failed to start the terminal UI, using plain output: {}", e);
+            return None;
+        }
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    let thread = std::thread::spawn(move || {
+        let result = run(&mut terminal, &state, &stopped);
+        ratatui::restore();
+        if let Err(e) = result {
+            error!("Terminal UI failed: {}", e);
+        }
+        let _ = quit.send(());
+    });
+    Some(Tui { stop, thread })
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    state: &SharedState,
+    stop: &AtomicBool,
+) -> std::io::Result<()> {
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        terminal.draw(|frame| draw(frame, &state.lock().unwrap()))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+
+        let mut state = state.lock().unwrap();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            // Raw mode swallows SIGINT, so handle Ctrl+C ourselves
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('a') => {
+                state.toggle_auto_accept();
+                let action = format!("Auto-accept {}", on_off(state.auto_accept_enabled));
+                state.record_action(action);
+            }
+            KeyCode::Char('d') => {
+                state.toggle_dodge();
+                let action = if state.dodge.enabled.is_some() {
+                    "Dodge armed"
+                } else {
+                    "Dodge disarmed"
+                };
+                state.record_action(action.to_string());
+            }
+            KeyCode::Char('o') => state.reopen_requested = true,
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, state: &AppState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),
+            Constraint::Min(8),
+            Constraint::Length(12),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+    let top = split_columns(rows[0]);
+    let bottom = split_columns(rows[2]);
+
+    frame.render_widget(status(state), top[0]);
+    frame.render_widget(phase_history(state), top[1]);
+    frame.render_widget(board(state), rows[1]);
+    frame.render_widget(tail("Automation", &state.actions, bottom[0]), bottom[0]);
+    frame.render_widget(tail("Log", &state.log, bottom[1]), bottom[1]);
+    frame.render_widget(Paragraph::new(HELP), rows[3]);
+}
+
+fn split_columns(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area)
+}
+
+fn status(state: &AppState) -> Paragraph<'static> {
+    let connection = match (&state.lcu.data, state.lcu.connected) {
+        (Some(info), true) => format!("connected (port {})", info.port),
+        _ => "waiting for League Client".to_string(),
+    };
+    let dodge = match state.dodge.enabled {
+        Some(_) => "armed".to_string(),
+        None => "disarmed".to_string(),
+    };
+    let last_dodge = match state.dodge.last_dodge {
+        Some(at) => format!("{} ago", format_duration(now_ms().saturating_sub(at))),
+        None => "never".to_string(),
+    };
+
+    let lines = [
+        format!("League Client: {}", connection),
+        format!("Phase: {}", state.current_phase().unwrap_or("-")),
+        format!("Auto-accept: {}", on_off(state.auto_accept_enabled)),
+        format!("Dodge: {} (last: {})", dodge, last_dodge),
+        format!("Last links: {}", state.last_links.len()),
+    ];
+    Paragraph::new(lines.join("\n")).block(Block::default().borders(Borders::ALL).title("Status"))
+}
+
+fn phase_history(state: &AppState) -> List<'static> {
+    let now = now_ms();
+    let changes: Vec<_> = state.phase_history.iter().collect();
+    let items: Vec<ListItem> = changes
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, change)| {
+            let until = changes.get(i + 1).map(|next| next.at).unwrap_or(now);
+            ListItem::new(format!(
+                "{:<20}{}",
+                change.phase,
+                format_duration(until.saturating_sub(change.at))
+            ))
+        })
+        .collect();
+    List::new(items).block(Block::default().borders(Borders::ALL).title("Phase history"))
+}
+
+fn board(state: &AppState) -> Paragraph<'static> {
+    let text = if state.champ_select_board.is_empty() {
+        "Not in champ select".to_string()
+    } else {
+        state.champ_select_board.join("\n")
+    };
+    Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Champ select"))
+}
+
+/// The newest lines that fit in `area`
+fn tail<'a>(
+    title: &'a str,
+    lines: &std::collections::VecDeque<String>,
+    area: Rect,
+) -> List<'a> {
+    let visible = area.height.saturating_sub(2) as usize;
+    let items: Vec<ListItem> = lines
+        .iter()
+        .skip(lines.len().saturating_sub(visible))
+        .map(|line| ListItem::new(line.clone()))
+        .collect();
+    List::new(items).block(Block::default().borders(Borders::ALL).title(title))
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
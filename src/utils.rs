@@ -1,6 +1,18 @@
-use crate::lobby::{Lobby, Participant};
+use crate::browser::Browser;
+use crate::config::TftProvider;
+use crate::lobby::{position_label, Lobby, Participant};
+use crate::logging;
+use crate::region::RegionInfo;
+use tracing::{info, warn};
 use urlencoding::encode;
 
+/// Multi-search used in Arena when the configured site has no Arena support
+const ARENA_FALLBACK_SITE: &str = "opgg";
+
+pub fn supports_arena(site: &str) -> bool {
+    matches!(site, "opgg" | "ugg")
+}
+
 pub fn create_opgg_link(summoners: &Vec<Participant>, region: &str) -> String {
     let base_url = format!("https://www.op.gg/multisearch/{}?summoners=", region);
     let mut link_path = String::new();
@@ -57,39 +69,138 @@ pub fn create_tracker_link(summoners: &Vec<Participant>, region: &str) -> String
     format!("{}{}", base_url, encoded_path)
 }
 
-pub fn display_champ_select(lobby: &Lobby, region: &str, site: &str) {
-    if lobby.participants.is_empty() {
-        return;
-    }
+pub fn create_lolchess_link(summoner: &Participant, region: &str) -> String {
+    format!(
+        "https://lolchess.gg/profile/{}/{}-{}",
+        region,
+        encode(&summoner.game_name),
+        encode(&summoner.game_tag)
+    )
+}
+
+pub fn create_tactics_link(summoner: &Participant, region: &str) -> String {
+    format!(
+        "https://tactics.tools/player/{}/{}/{}",
+        region,
+        encode(&summoner.game_name),
+        encode(&summoner.game_tag)
+    )
+}
+
+pub fn create_metatft_link(summoner: &Participant, platform: &str) -> String {
+    format!(
+        "https://www.metatft.com/player/{}/{}-{}",
+        platform,
+        encode(&summoner.game_name),
+        encode(&summoner.game_tag)
+    )
+}
 
-    let mut team_string = String::new();
-    for summoner in lobby.participants.iter() {
-        let participant = format!(
-            "{}#{} ({})",
-            summoner.game_name, summoner.game_tag, summoner.name
-        );
-        team_string.push_str(&participant);
-        if summoner.name != lobby.participants.last().unwrap().name {
-            team_string.push_str(", ");
+pub fn format_team(lobby: &Lobby) -> logging::Sensitive {
+    let participants = lobby.participants.iter().map(|summoner| {
+        let mut participant = logging::riot_id(&summoner.game_name, &summoner.game_tag);
+        if let Some(role) = position_label(&summoner.assigned_position) {
+            participant.push_str(&format!(" [{}]", role));
         }
+        participant
+    });
+    logging::Sensitive::join(participants, ", ")
+}
+
+/// Prints the team and opens its multi-search, returning the opened link
+pub fn display_champ_select(
+    lobby: &Lobby,
+    region: &str,
+    site: &str,
+    browser: &Browser,
+) -> Option<String> {
+    if lobby.participants.is_empty() {
+        return None;
     }
 
-    println!("Team: {}", team_string);
-    let link = match site {
+    let team_string = format_team(lobby);
+    info!("Team: {}", team_string);
+    Some(open_multi_search(lobby, region, site, browser))
+}
+
+pub fn multi_search_link(lobby: &Lobby, region: &str, site: &str) -> String {
+    match site {
         "opgg" => create_opgg_link(&lobby.participants, region),
         "deeplol" => create_deeplol_link(&lobby.participants, region),
         "ugg" => create_ugg_link(&lobby.participants, format!("{}1", region)),
         "tracker" => create_tracker_link(&lobby.participants, region),
         _ => panic!("Invalid site"),
-    };
+    }
+}
 
-    println!("Opening link: {}", link);
-    match open::that(&link) {
-        Ok(_) => {
-            println!("Successfully opened link in browser");
-        }
-        Err(e) => {
-            println!("Failed to open link in browser: {:?}", e);
-        }
+fn open_multi_search(lobby: &Lobby, region: &str, site: &str, browser: &Browser) -> String {
+    let link = multi_search_link(lobby, region, site);
+    info!("Opening link: {}", logging::url(&link));
+    browser.open(&link);
+    link
+}
+
+/// Arena is played in duos, so only our duo goes into the multi-search
+pub fn display_arena_champ_select(
+    duo: &Lobby,
+    others: &Lobby,
+    region: &str,
+    site: &str,
+    browser: &Browser,
+) -> Option<String> {
+    if !others.participants.is_empty() {
+        info!("Other players: {}", format_team(others));
+    }
+
+    let site = arena_site(site);
+    if duo.participants.is_empty() {
+        return None;
     }
-}
\ No newline at end of file
+    info!("Duo: {}", format_team(duo));
+    Some(open_multi_search(duo, region, site, browser))
+}
+
+/// The site to use in Arena, falling back when `site` has no Arena support
+pub fn arena_site(site: &str) -> &str {
+    if supports_arena(site) {
+        return site;
+    }
+    warn!(
+        "{} does not support Arena, using {} instead",
+        site, ARENA_FALLBACK_SITE
+    );
+    ARENA_FALLBACK_SITE
+}
+
+/// TFT sites have no multi-search, so this opens one profile per player
+pub fn display_tft_links(
+    lobby: &Lobby,
+    region_info: &RegionInfo,
+    provider: TftProvider,
+    browser: &Browser,
+) -> Vec<String> {
+    if lobby.participants.is_empty() {
+        return Vec::new();
+    }
+
+    info!("Players: {}", format_team(lobby));
+    let links = tft_links(lobby, region_info, provider);
+    for link in &links {
+        info!("Opening link: {}", logging::url(link));
+        browser.open(link);
+    }
+    links
+}
+
+/// One profile link per player
+pub fn tft_links(lobby: &Lobby, region_info: &RegionInfo, provider: TftProvider) -> Vec<String> {
+    lobby
+        .participants
+        .iter()
+        .map(|summoner| match provider {
+            TftProvider::Lolchess => create_lolchess_link(summoner, &region_info.tft_region()),
+            TftProvider::Tactics => create_tactics_link(summoner, &region_info.tft_region()),
+            TftProvider::Metatft => create_metatft_link(summoner, &region_info.platform_id()),
+        })
+        .collect()
+}
@@ -0,0 +1,355 @@
+use crate::config::WebhookConfig;
+use crate::events::{EventPayload, GameEvent};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Sends configured webhooks for client events
+pub struct Webhooks {
+    hooks: Arc<Vec<WebhookConfig>>,
+    client: Client,
+}
+
+impl Webhooks {
+    pub fn new(hooks: &[WebhookConfig]) -> Self {
+        Webhooks {
+            hooks: Arc::new(hooks.to_vec()),
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Delivers in the background so a slow endpoint never holds up polling
+    pub fn notify(&self, payload: &EventPayload) {
+        for (index, hook) in self.hooks.iter().enumerate() {
+            if !routes(hook, payload) {
+                continue;
+            }
+            let body = match render(hook, payload) {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!("Webhook {} skipped: {}", hook.url, e);
+                    continue;
+                }
+            };
+
+            let client = self.client.clone();
+            let hooks = self.hooks.clone();
+            tokio::spawn(async move {
+                let hook = &hooks[index];
+                if let Err(e) = deliver(&client, hook, body).await {
+                    warn!("Webhook {} failed: {}", hook.url, e);
+                }
+            });
+        }
+    }
+}
+
+/// An empty event list means every event
+fn routes(hook: &WebhookConfig, payload: &EventPayload) -> bool {
+    hook.events.is_empty() || hook.events.contains(&payload.event)
+}
+
+/// The payload as JSON, or the hook's template with `{{field}}` placeholders
+/// filled in. Strings are JSON-escaped. Missing values become empty inside a
+/// quoted string and `null` anywhere else.
+pub fn render(hook: &WebhookConfig, payload: &EventPayload) -> Result<String, String> {
+    let value = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+    let template = match &hook.template {
+        Some(template) => template,
+        None => return Ok(value.to_string()),
+    };
+
+    let body = match &value {
+        serde_json::Value::Object(fields) => fill(template, fields),
+        _ => template.clone(),
+    };
+    serde_json::from_str::<serde_json::Value>(&body)
+        .map_err(|e| format!("template is not valid JSON once filled in: {}", e))?;
+    Ok(body)
+}
+
+/// Renders `hook` with every field missing and with every field set, so a
+/// template that only works for some events is caught at startup
+pub fn check_template(hook: &WebhookConfig) -> Result<(), String> {
+    let empty = EventPayload::new(GameEvent::ReadyCheck, None, None, None, None);
+    let full = EventPayload {
+        event: GameEvent::GameEnded,
+        at: chrono::Utc::now(),
+        queue_id: Some(420),
+        queue: Some("Ranked Solo/Duo".to_string()),
+        game_mode: Some("CLASSIC".to_string()),
+        game_id: Some(1),
+        region: Some("EUW".to_string()),
+        duration_secs: Some(1),
+    };
+    render(hook, &empty)?;
+    render(hook, &full)?;
+    Ok(())
+}
+
+/// Replaces known placeholders, keeping track of whether each one is inside a
+/// JSON string
+fn fill(template: &str, fields: &serde_json::Map<String, serde_json::Value>) -> String {
+    let mut body = String::with_capacity(template.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") {
+            let field = rest
+                .find("}}")
+                .and_then(|end| fields.get(&rest[2..end]).map(|field| (end, field)));
+            if let Some((end, field)) = field {
+                body.push_str(&placeholder(field, in_string));
+                rest = &rest[end + 2..];
+                escaped = false;
+                continue;
+            }
+        }
+
+        if in_string && !escaped && c == '"' {
+            in_string = false;
+        } else if !in_string && c == '"' {
+            in_string = true;
+        }
+        escaped = in_string && c == '\\' && !escaped;
+        body.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    body
+}
+
+fn placeholder(field: &serde_json::Value, quoted: bool) -> String {
+    match field {
+        serde_json::Value::Null if quoted => String::new(),
+        serde_json::Value::String(text) => {
+            let json = serde_json::to_string(text).unwrap_or_default();
+            json[1..json.len() - 1].to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+/// POSTs `body`, retrying network errors, 429s and 5xx with exponential backoff
+pub async fn deliver(client: &Client, hook: &WebhookConfig, body: String) -> Result<(), String> {
+    let mut backoff = Duration::from_millis(hook.retry_backoff_ms);
+    let mut attempt = 0;
+    loop {
+        let mut request = client
+            .post(&hook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        for (name, value) in &hook.headers {
+            request = request.header(name, value);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("Webhook {} delivered", hook.url);
+                return Ok(());
+            }
+            Ok(response) => {
+                let status = response.status();
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    return Err(format!("rejected with {}", status));
+                }
+                format!("{}", status)
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if attempt >= hook.max_retries {
+            return Err(format!("giving up after {} attempts: {}", attempt + 1, error));
+        }
+        attempt += 1;
+        debug!("Webhook {} failed ({}), retrying in {:?}", hook.url, error, backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameEvent;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Local stand-in for a webhook receiver that fails the first `failures` requests
+    struct StandIn {
+        failures: usize,
+        status: StatusCode,
+        bodies: Mutex<Vec<String>>,
+    }
+
+    async fn receive(State(stand_in): State<Arc<StandIn>>, body: String) -> StatusCode {
+        let mut bodies = stand_in.bodies.lock().unwrap();
+        bodies.push(body);
+        if bodies.len() <= stand_in.failures {
+            stand_in.status
+        } else {
+            StatusCode::OK
+        }
+    }
+
+    async fn serve(failures: usize, status: StatusCode) -> (String, Arc<StandIn>) {
+        let stand_in = Arc::new(StandIn {
+            failures,
+            status,
+            bodies: Mutex::new(Vec::new()),
+        });
+        let router = Router::new()
+            .route("/hook", post(receive))
+            .with_state(stand_in.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (url, stand_in)
+    }
+
+    fn hook(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            events: Vec::new(),
+            template: None,
+            headers: HashMap::new(),
+            max_retries: 3,
+            retry_backoff_ms: 1,
+        }
+    }
+
+    fn payload(event: GameEvent) -> EventPayload {
+        EventPayload {
+            event,
+            at: chrono::Utc::now(),
+            queue_id: Some(420),
+            queue: Some("Ranked \"Solo\"/Duo".to_string()),
+            game_mode: Some("CLASSIC".to_string()),
+            game_id: None,
+            region: Some("EUW".to_string()),
+            duration_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_delivered() {
+        let (url, stand_in) = serve(2, StatusCode::SERVICE_UNAVAILABLE).await;
+        let hook = hook(&url);
+        let body = render(&hook, &payload(GameEvent::ReadyCheck)).unwrap();
+
+        deliver(&Client::new(), &hook, body).await.unwrap();
+
+        let bodies = stand_in.bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 3);
+        let json: serde_json::Value = serde_json::from_str(&bodies[2]).unwrap();
+        assert_eq!(json["event"], "ready_check");
+        assert_eq!(json["queue_id"], 420);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, stand_in) = serve(usize::MAX, StatusCode::INTERNAL_SERVER_ERROR).await;
+        let mut hook = hook(&url);
+        hook.max_retries = 2;
+
+        let result = deliver(&Client::new(), &hook, "{}".to_string()).await;
+
+        assert!(result.is_err());
+        assert_eq!(stand_in.bodies.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (url, stand_in) = serve(usize::MAX, StatusCode::BAD_REQUEST).await;
+
+        let result = deliver(&Client::new(), &hook(&url), "{}".to_string()).await;
+
+        assert!(result.is_err());
+        assert_eq!(stand_in.bodies.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn notify_routes_by_event() {
+        let (url, stand_in) = serve(0, StatusCode::OK).await;
+        let mut routed = hook(&url);
+        routed.events = vec![GameEvent::Accepted];
+        let webhooks = Webhooks::new(&[routed]);
+
+        webhooks.notify(&payload(GameEvent::ReadyCheck));
+        webhooks.notify(&payload(GameEvent::Accepted));
+        for _ in 0..100 {
+            if !stand_in.bodies.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let bodies = stand_in.bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains("\"accepted\""));
+    }
+
+    #[test]
+    fn template_escapes_strings_and_blanks_missing_values() {
+        let mut hook = hook("http://unused");
+        hook.template = Some(
+            r#"{"text": "{{event}} in {{queue}}", "game": "{{game_id}}", "queue_id": {{queue_id}}}"#
+                .to_string(),
+        );
+
+        let body = render(&hook, &payload(GameEvent::ReadyCheck)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(json["text"], "ready_check in Ranked \"Solo\"/Duo");
+        assert_eq!(json["game"], "");
+        assert_eq!(json["queue_id"], 420);
+    }
+
+    #[test]
+    fn unquoted_missing_values_are_null() {
+        let mut hook = hook("http://unused");
+        hook.template = Some(
+            r#"{"queue_id": {{queue_id}}, "game_id": {{game_id}}, "note": "{{game_id}} \"{{duration_secs}}\""}"#
+                .to_string(),
+        );
+
+        let body = render(&hook, &payload(GameEvent::ReadyCheck)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(json["queue_id"], 420);
+        assert_eq!(json["game_id"], serde_json::Value::Null);
+        assert_eq!(json["note"], " \"\"");
+        assert!(check_template(&hook).is_ok());
+    }
+
+    #[test]
+    fn template_that_breaks_once_set_is_rejected() {
+        let mut hook = hook("http://unused");
+        // Fine while the queue is unknown, invalid once it is a string
+        hook.template = Some(r#"{"queue": {{queue}}}"#.to_string());
+
+        let empty = EventPayload::new(GameEvent::ReadyCheck, None, None, None, None);
+        assert!(render(&hook, &empty).is_ok());
+        assert!(check_template(&hook).is_err());
+    }
+
+    #[test]
+    fn invalid_template_is_rejected() {
+        let mut hook = hook("http://unused");
+        hook.template = Some(r#"{"queue": {{queue}}}"#.to_string());
+
+        assert!(render(&hook, &payload(GameEvent::ReadyCheck)).is_err());
+    }
+}